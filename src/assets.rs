@@ -0,0 +1,24 @@
+use magic_wars::rules::{
+    character::CharacterTypes, data::GameData, error::MapError, grid::ParseableMap,
+    grid::TerrainTypes, spells::Spells,
+};
+use quicksilver::load_file;
+
+async fn read(file: &str) -> Result<Vec<u8>, MapError> {
+    load_file(file)
+        .await
+        .map_err(|error| MapError::CouldNotLoad(error.to_string()))
+}
+
+/// Loads everything from the static folder the rules need.
+pub async fn load_game_data() -> Result<GameData, MapError> {
+    Ok(GameData {
+        character_types: CharacterTypes::from_json(&read("units.json").await?)?,
+        terrain: TerrainTypes::from_json(&read("terrain.json").await?)?,
+        spells: Spells::from_json(&read("spells.json").await?)?,
+    })
+}
+
+pub async fn load_map(file: &str) -> Result<ParseableMap, MapError> {
+    Ok(serde_json::from_slice(&read(file).await?)?)
+}
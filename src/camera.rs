@@ -0,0 +1,114 @@
+use magic_wars::rules::grid::{Grid, TILE_SIZE};
+use quicksilver::{
+    geom::{Transform, Vector},
+    mint::Vector2,
+};
+
+const ZOOM_LEVELS: [f32; 5] = [0.5, 0.75, 1., 1.5, 2.];
+const DEFAULT_ZOOM: usize = 2;
+/// How close to the edge of the window, in pixels, the cursor needs to be to scroll.
+const EDGE_SIZE: f32 = 16.;
+
+/// The direction to scroll in when the cursor is at the edge of the window.
+pub fn edge_direction(cursor: Vector, window_size: Vector) -> Vector {
+    let axis = |pos: f32, size: f32| {
+        if pos < EDGE_SIZE {
+            -1.
+        } else if pos > size - EDGE_SIZE {
+            1.
+        } else {
+            0.
+        }
+    };
+    Vector::new(axis(cursor.x, window_size.x), axis(cursor.y, window_size.y))
+}
+
+/// Which part of the map is visible and how big it is drawn.
+#[derive(Clone, PartialEq)]
+pub struct Camera {
+    /// The point of the map, in pixels at normal zoom, shown in the top left corner of the window.
+    position: Vector,
+    zoom_level: usize,
+    /// How fast the view scrolls right now, in screen pixels per update.
+    velocity: Vector,
+}
+
+impl Camera {
+    pub fn new() -> Self {
+        Self {
+            position: Vector::new(0., 0.),
+            zoom_level: DEFAULT_ZOOM,
+            velocity: Vector::new(0., 0.),
+        }
+    }
+    pub fn get_zoom(&self) -> f32 {
+        ZOOM_LEVELS[self.zoom_level]
+    }
+    /// The transform to draw the map with.
+    pub fn get_transform(&self) -> Transform {
+        Transform::scale(Vector::new(self.get_zoom(), self.get_zoom()))
+            * Transform::translate(self.position * -1.)
+    }
+    pub fn screen_to_world(&self, pos: Vector) -> Vector {
+        pos * (1. / self.get_zoom()) + self.position
+    }
+    pub fn screen_to_grid(&self, pos: Vector) -> Vector2<i32> {
+        let world = self.screen_to_world(pos);
+        [
+            (world.x / TILE_SIZE as f32).floor() as i32,
+            (world.y / TILE_SIZE as f32).floor() as i32,
+        ]
+        .into()
+    }
+    /// Moves the view by the given amount of screen pixels.
+    pub fn pan(&mut self, by: Vector) {
+        self.position += by * (1. / self.get_zoom());
+    }
+    /// Speeds up or slows down towards scrolling in `direction` at `speed`, then scrolls.
+    /// `step` is the part of `speed` that the velocity may change by.
+    pub fn scroll(&mut self, direction: Vector, speed: f32, step: f32) {
+        let max_change = speed * step;
+        let approach = |current: f32, target: f32| {
+            current + (target - current).max(-max_change).min(max_change)
+        };
+        self.velocity = Vector::new(
+            approach(self.velocity.x, direction.x * speed),
+            approach(self.velocity.y, direction.y * speed),
+        );
+        self.pan(self.velocity);
+    }
+    pub fn stop(&mut self) {
+        self.velocity = Vector::new(0., 0.);
+    }
+    /// Zooms in or out by `steps` levels, keeping the tile under `anchor` in place.
+    pub fn zoom(&mut self, steps: i32, anchor: Vector) {
+        let before = self.screen_to_world(anchor);
+        let level = (self.zoom_level as i32 + steps).max(0) as usize;
+        self.zoom_level = level.min(ZOOM_LEVELS.len() - 1);
+        self.position = before - anchor * (1. / self.get_zoom());
+    }
+    pub fn center_on(&mut self, pos: Vector2<i32>, window_size: Vector) {
+        let tile_center = Vector::new(
+            (pos.x as f32 + 0.5) * TILE_SIZE as f32,
+            (pos.y as f32 + 0.5) * TILE_SIZE as f32,
+        );
+        self.position = tile_center - window_size * (0.5 / self.get_zoom());
+    }
+    /// Whether the tile is fully visible, with at least `margin` tiles between it and the edge.
+    pub fn shows(&self, pos: Vector2<i32>, margin: i32, window_size: Vector) -> bool {
+        let top_left = self.screen_to_grid(Vector::new(0., 0.));
+        let bottom_right = self.screen_to_grid(window_size);
+        pos.x - margin > top_left.x
+            && pos.y - margin > top_left.y
+            && pos.x + margin < bottom_right.x
+            && pos.y + margin < bottom_right.y
+    }
+    /// Keeps the view on the map. Maps smaller than the window stay in the top left.
+    pub fn clamp(&mut self, grid: &Grid, window_size: Vector) {
+        let view = window_size * (1. / self.get_zoom());
+        let max_x = (grid.width() * TILE_SIZE) as f32 - view.x;
+        let max_y = (grid.height() * TILE_SIZE) as f32 - view.y;
+        self.position.x = self.position.x.min(max_x).max(0.);
+        self.position.y = self.position.y.min(max_y).max(0.);
+    }
+}
@@ -0,0 +1,103 @@
+use crate::{grid_pos_to_rectangle, Wrapper};
+use magic_wars::rules::{
+    character::{Character, CharacterContainer},
+    grid::{Grid, Terrain},
+};
+use quicksilver::{
+    geom::{Rectangle, Vector},
+    graphics::Color,
+    Result,
+};
+
+const TEAM_COLORS: [Color; 4] = [Color::RED, Color::BLUE, Color::YELLOW, Color::PURPLE];
+
+pub fn team_color(team: usize) -> Color {
+    TEAM_COLORS[team % TEAM_COLORS.len()]
+}
+
+fn terrain_color(terrain: &Terrain) -> Color {
+    let [r, g, b] = terrain.color;
+    Color {
+        r: f32::from(r) / 255.,
+        g: f32::from(g) / 255.,
+        b: f32::from(b) / 255.,
+        a: 1.,
+    }
+}
+
+pub fn draw_grid(grid: &Grid, wrapper: &mut Wrapper<'_>) {
+    grid.iter().for_each(|(pos, tile)| {
+        let rectangle = grid_pos_to_rectangle(pos);
+        let sprite = tile
+            .sprite
+            .as_ref()
+            .and_then(|sprite| wrapper.loaded_images.get(sprite))
+            .cloned();
+        match sprite {
+            Some(sprite) => wrapper.gfx.draw_image(&sprite, rectangle),
+            None => wrapper.gfx.fill_rect(&rectangle, terrain_color(tile)),
+        }
+    })
+}
+
+fn draw_character(
+    character: &Character,
+    rectangle: Rectangle,
+    wrapper: &mut Wrapper<'_>,
+) -> Result<()> {
+    let color = team_color(character.team);
+    let color = if character.has_moved {
+        color.with_alpha(0.5)
+    } else {
+        color
+    };
+    let sprite = character
+        .char_type
+        .sprite
+        .as_ref()
+        .and_then(|sprite| wrapper.loaded_images.get(sprite))
+        .cloned();
+    if let Some(sprite) = sprite {
+        wrapper.gfx.draw_image(&sprite, rectangle);
+        wrapper.gfx.fill_rect(
+            &Rectangle::new(rectangle.pos, (rectangle.size.x, 6.)),
+            color,
+        );
+    } else {
+        wrapper.gfx.fill_rect(&rectangle, color);
+    }
+    let hp_left = character.hp.max(0) as f32 / character.char_type.get_max_hp() as f32;
+    wrapper.gfx.fill_rect(
+        &Rectangle::new(
+            rectangle.pos + Vector::new(0., rectangle.size.y - 6.),
+            (rectangle.size.x * hp_left, 6.),
+        ),
+        Color::GREEN,
+    );
+    if character.char_type.get_max_mana() > 0 {
+        let mana_left = character.mana.max(0) as f32 / character.char_type.get_max_mana() as f32;
+        wrapper.gfx.fill_rect(
+            &Rectangle::new(
+                rectangle.pos + Vector::new(0., rectangle.size.y - 12.),
+                (rectangle.size.x * mana_left, 6.),
+            ),
+            Color::CYAN,
+        );
+    }
+    Ok(())
+}
+
+pub fn draw_characters(characters: &CharacterContainer, wrapper: &mut Wrapper<'_>) -> Result<()> {
+    for (position, corpse) in &characters.corpses {
+        let rectangle = grid_pos_to_rectangle(*position);
+        wrapper.gfx.fill_rect(
+            &Rectangle::new(rectangle.pos + rectangle.size * 0.25, rectangle.size * 0.5),
+            team_color(corpse.team).with_alpha(0.3),
+        );
+    }
+    for character in characters.characters.values() {
+        let rectangle = grid_pos_to_rectangle(characters.get_drawn_position(character));
+        draw_character(character, rectangle, wrapper)?;
+    }
+    Ok(())
+}
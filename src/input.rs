@@ -0,0 +1,391 @@
+use quicksilver::{
+    geom::Vector,
+    lifecycle::{Event, GamepadAxis, GamepadButton, Key, MouseButton},
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, convert::TryFrom};
+
+/// Something the player can do, no matter which key or button it is bound to.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum Action {
+    PanUp,
+    PanDown,
+    PanLeft,
+    PanRight,
+    DragPan,
+    CursorUp,
+    CursorDown,
+    CursorLeft,
+    CursorRight,
+    Confirm,
+    Cancel,
+    Undo,
+    EndTurn,
+    NextUnit,
+    PreviousUnit,
+}
+
+/// Every action, in the order they are shown on the controls screen.
+pub const ACTIONS: [Action; 15] = [
+    Action::PanUp,
+    Action::PanDown,
+    Action::PanLeft,
+    Action::PanRight,
+    Action::DragPan,
+    Action::CursorUp,
+    Action::CursorDown,
+    Action::CursorLeft,
+    Action::CursorRight,
+    Action::Confirm,
+    Action::Cancel,
+    Action::Undo,
+    Action::EndTurn,
+    Action::NextUnit,
+    Action::PreviousUnit,
+];
+
+impl Action {
+    pub fn get_name(self) -> &'static str {
+        match self {
+            Action::PanUp => "Scroll up",
+            Action::PanDown => "Scroll down",
+            Action::PanLeft => "Scroll left",
+            Action::PanRight => "Scroll right",
+            Action::DragPan => "Drag the map",
+            Action::CursorUp => "Cursor up",
+            Action::CursorDown => "Cursor down",
+            Action::CursorLeft => "Cursor left",
+            Action::CursorRight => "Cursor right",
+            Action::Confirm => "Confirm",
+            Action::Cancel => "Cancel",
+            Action::Undo => "Undo",
+            Action::EndTurn => "End turn",
+            Action::NextUnit => "Next unit",
+            Action::PreviousUnit => "Previous unit",
+        }
+    }
+}
+
+/// The keys that can be bound, anything else can't be written to the keymap.
+const KEYS: [Key; 67] = [
+    Key::Key1,
+    Key::Key2,
+    Key::Key3,
+    Key::Key4,
+    Key::Key5,
+    Key::Key6,
+    Key::Key7,
+    Key::Key8,
+    Key::Key9,
+    Key::Key0,
+    Key::A,
+    Key::B,
+    Key::C,
+    Key::D,
+    Key::E,
+    Key::F,
+    Key::G,
+    Key::H,
+    Key::I,
+    Key::J,
+    Key::K,
+    Key::L,
+    Key::M,
+    Key::N,
+    Key::O,
+    Key::P,
+    Key::Q,
+    Key::R,
+    Key::S,
+    Key::T,
+    Key::U,
+    Key::V,
+    Key::W,
+    Key::X,
+    Key::Y,
+    Key::Z,
+    Key::Escape,
+    Key::F1,
+    Key::F2,
+    Key::F3,
+    Key::F4,
+    Key::F5,
+    Key::F6,
+    Key::F7,
+    Key::F8,
+    Key::F9,
+    Key::F10,
+    Key::F11,
+    Key::F12,
+    Key::Left,
+    Key::Up,
+    Key::Right,
+    Key::Down,
+    Key::Back,
+    Key::Return,
+    Key::Space,
+    Key::Tab,
+    Key::LShift,
+    Key::RShift,
+    Key::LControl,
+    Key::RControl,
+    Key::Home,
+    Key::End,
+    Key::PageUp,
+    Key::PageDown,
+    Key::Delete,
+    Key::Insert,
+];
+
+/// Actions the menus can't be used without, so they always keep a binding on every device that has one.
+const REQUIRED: [Action; 2] = [Action::Confirm, Action::Cancel];
+
+/// The left mouse button always points at things, so it can't be bound.
+const MOUSE_BUTTONS: [MouseButton; 2] = [MouseButton::Right, MouseButton::Middle];
+
+const GAMEPAD_BUTTONS: [GamepadButton; 16] = [
+    GamepadButton::South,
+    GamepadButton::East,
+    GamepadButton::North,
+    GamepadButton::West,
+    GamepadButton::LeftShoulder,
+    GamepadButton::LeftTrigger,
+    GamepadButton::RightShoulder,
+    GamepadButton::RightTrigger,
+    GamepadButton::Select,
+    GamepadButton::Start,
+    GamepadButton::LeftStick,
+    GamepadButton::RightStick,
+    GamepadButton::DPadUp,
+    GamepadButton::DPadDown,
+    GamepadButton::DPadLeft,
+    GamepadButton::DPadRight,
+];
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub enum Binding {
+    Key(Key),
+    Mouse(MouseButton),
+    Gamepad(GamepadButton),
+}
+
+impl Binding {
+    /// The binding an event came from and whether it got pressed or released.
+    pub fn from_event(event: &Event) -> Option<(Binding, bool)> {
+        match event {
+            Event::KeyboardInput(x) => Some((Binding::Key(x.key()), x.is_down())),
+            Event::PointerInput(x) => Some((Binding::Mouse(x.button()), x.is_down())),
+            Event::GamepadButton(x) => Some((Binding::Gamepad(x.button()), x.is_down())),
+            _ => None,
+        }
+    }
+    pub fn can_be_bound(self) -> bool {
+        match self {
+            Binding::Key(key) => KEYS.contains(&key),
+            Binding::Mouse(button) => MOUSE_BUTTONS.contains(&button),
+            Binding::Gamepad(button) => GAMEPAD_BUTTONS.contains(&button),
+        }
+    }
+    /// Keyboards and mice count as one device, so an action can have a key and a gamepad button.
+    fn is_gamepad(self) -> bool {
+        match self {
+            Binding::Gamepad(_) => true,
+            _ => false,
+        }
+    }
+}
+
+impl From<Binding> for String {
+    fn from(binding: Binding) -> String {
+        match binding {
+            Binding::Key(key) => format!("{:?}", key),
+            Binding::Mouse(button) => format!("Mouse {:?}", button),
+            Binding::Gamepad(button) => format!("Gamepad {:?}", button),
+        }
+    }
+}
+
+impl TryFrom<String> for Binding {
+    type Error = String;
+    fn try_from(name: String) -> Result<Self, Self::Error> {
+        KEYS.iter()
+            .copied()
+            .map(Binding::Key)
+            .chain(MOUSE_BUTTONS.iter().copied().map(Binding::Mouse))
+            .chain(GAMEPAD_BUTTONS.iter().copied().map(Binding::Gamepad))
+            .find(|binding| String::from(*binding) == name)
+            .ok_or_else(|| format!("{} can not be bound", name))
+    }
+}
+
+/// Which keys and buttons trigger which action.
+/// It gets saved as json, so it can be edited by hand as well.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Keymap {
+    bindings: HashMap<Action, Vec<Binding>>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let defaults = vec![
+            (Action::PanUp, Binding::Key(Key::W)),
+            (Action::PanDown, Binding::Key(Key::S)),
+            (Action::PanLeft, Binding::Key(Key::A)),
+            (Action::PanRight, Binding::Key(Key::D)),
+            (Action::DragPan, Binding::Mouse(MouseButton::Middle)),
+            (Action::CursorUp, Binding::Key(Key::Up)),
+            (Action::CursorDown, Binding::Key(Key::Down)),
+            (Action::CursorLeft, Binding::Key(Key::Left)),
+            (Action::CursorRight, Binding::Key(Key::Right)),
+            (Action::Confirm, Binding::Key(Key::Return)),
+            (Action::Cancel, Binding::Key(Key::Escape)),
+            (Action::Undo, Binding::Mouse(MouseButton::Right)),
+            (Action::EndTurn, Binding::Key(Key::E)),
+            (Action::NextUnit, Binding::Key(Key::Tab)),
+            (Action::CursorUp, Binding::Gamepad(GamepadButton::DPadUp)),
+            (
+                Action::CursorDown,
+                Binding::Gamepad(GamepadButton::DPadDown),
+            ),
+            (
+                Action::CursorLeft,
+                Binding::Gamepad(GamepadButton::DPadLeft),
+            ),
+            (
+                Action::CursorRight,
+                Binding::Gamepad(GamepadButton::DPadRight),
+            ),
+            (Action::Confirm, Binding::Gamepad(GamepadButton::South)),
+            (Action::Cancel, Binding::Gamepad(GamepadButton::East)),
+            (Action::Undo, Binding::Gamepad(GamepadButton::West)),
+            (Action::EndTurn, Binding::Gamepad(GamepadButton::North)),
+            (
+                Action::NextUnit,
+                Binding::Gamepad(GamepadButton::RightShoulder),
+            ),
+            (
+                Action::PreviousUnit,
+                Binding::Gamepad(GamepadButton::LeftShoulder),
+            ),
+        ];
+        let mut bindings = HashMap::new();
+        for (action, binding) in defaults {
+            bindings
+                .entry(action)
+                .or_insert_with(Vec::new)
+                .push(binding);
+        }
+        Self { bindings }
+    }
+}
+
+impl Keymap {
+    pub fn get_bindings(&self, action: Action) -> &[Binding] {
+        self.bindings
+            .get(&action)
+            .map(|bindings| bindings.as_slice())
+            .unwrap_or(&[])
+    }
+    pub fn get_action(&self, binding: Binding) -> Option<Action> {
+        ACTIONS
+            .iter()
+            .copied()
+            .find(|action| self.get_bindings(*action).contains(&binding))
+    }
+    /// The action an event triggers and whether it got pressed or released.
+    pub fn action_of(&self, event: &Event) -> Option<(Action, bool)> {
+        let (binding, is_down) = Binding::from_event(event)?;
+        self.get_action(binding).map(|action| (action, is_down))
+    }
+    /// Makes `binding` the only way to trigger `action` on its device, taking it away from other actions.
+    pub fn bind(&mut self, action: Action, binding: Binding) {
+        for bindings in self.bindings.values_mut() {
+            bindings.retain(|existing| *existing != binding);
+        }
+        let bindings = self.bindings.entry(action).or_insert_with(Vec::new);
+        bindings.retain(|existing| existing.is_gamepad() != binding.is_gamepad());
+        bindings.push(binding);
+    }
+    /// The required action that would be left without a binding on that device if `binding` went to `action`.
+    pub fn would_leave_unbound(&self, action: Action, binding: Binding) -> Option<Action> {
+        REQUIRED
+            .iter()
+            .copied()
+            .filter(|required| *required != action)
+            .find(|required| {
+                let bindings = self.get_bindings(*required);
+                bindings.contains(&binding)
+                    && !bindings.iter().any(|other| {
+                        *other != binding && other.is_gamepad() == binding.is_gamepad()
+                    })
+            })
+    }
+    /// Gives actions that have nothing bound on a device in a saved keymap their default binding for it,
+    /// as long as that binding isn't in use by another action.
+    pub fn fill_missing(&mut self) {
+        for (action, defaults) in Keymap::default().bindings {
+            for binding in defaults {
+                let has_device = self
+                    .get_bindings(action)
+                    .iter()
+                    .any(|existing| existing.is_gamepad() == binding.is_gamepad());
+                if !has_device && self.get_action(binding).is_none() {
+                    self.bindings
+                        .entry(action)
+                        .or_insert_with(Vec::new)
+                        .push(binding);
+                }
+            }
+        }
+    }
+}
+
+/// Stick movement smaller than this is ignored.
+const DEAD_ZONE: f32 = 0.3;
+
+/// How far the analog sticks of a gamepad are pushed, with up being negative like on screen.
+/// The left stick moves the tile cursor and the right one the camera. They can't be rebound.
+pub struct Sticks {
+    pub cursor: Vector,
+    pub camera: Vector,
+}
+
+impl Sticks {
+    pub fn new() -> Self {
+        Self {
+            cursor: Vector::new(0., 0.),
+            camera: Vector::new(0., 0.),
+        }
+    }
+    pub fn event(&mut self, event: &Event) {
+        match event {
+            Event::GamepadAxis(x) => {
+                let value = if x.value().abs() < DEAD_ZONE {
+                    0.
+                } else {
+                    x.value()
+                };
+                match x.axis() {
+                    GamepadAxis::LeftStickX => self.cursor.x = value,
+                    GamepadAxis::LeftStickY => self.cursor.y = -value,
+                    GamepadAxis::RightStickX => self.camera.x = value,
+                    GamepadAxis::RightStickY => self.camera.y = -value,
+                }
+            }
+            Event::GamepadDisconnected(_) => *self = Sticks::new(),
+            _ => {}
+        }
+    }
+    /// The tile the left stick points to, going along whichever axis it is pushed the furthest.
+    pub fn get_cursor_step(&self) -> Option<[i32; 2]> {
+        let Vector { x, y } = self.cursor;
+        if x == 0. && y == 0. {
+            None
+        } else if x.abs() >= y.abs() {
+            Some([x.signum() as i32, 0])
+        } else {
+            Some([0, y.signum() as i32])
+        }
+    }
+}
@@ -1,4 +1,5 @@
-use grid::TILE_SIZE;
+use input::Keymap;
+use magic_wars::rules::{data::GameData, grid::TILE_SIZE};
 use mergui::{Context, MFont};
 use quicksilver::{
     geom::{Rectangle, Transform, Vector},
@@ -7,13 +8,18 @@ use quicksilver::{
     mint::Vector2,
     Result, Timer,
 };
-use screens::{game::Game, screen::Screen};
+use screens::{error::ErrorScreen, main_menu::MainMenu, screen::Screen, stack::ScreenStack};
+use settings::GameSettings;
 use std::collections::HashMap;
 
-mod character;
-mod grid;
+mod assets;
+mod camera;
+mod draw;
+mod input;
 mod panel;
+mod saves;
 mod screens;
+mod settings;
 
 pub struct Wrapper<'a> {
     pub window: Window,
@@ -23,6 +29,11 @@ pub struct Wrapper<'a> {
     pub last_cursor_pos: Vector2<f32>,
     pub loaded_font: Option<VectorFont>,
     pub loaded_images: HashMap<String, Image>,
+    pub settings: GameSettings,
+    pub keymap: Keymap,
+    pub data: GameData,
+    /// How many updates happened since the game started, used to seed random number generators.
+    pub ticks: u64,
 }
 impl<'a> Wrapper<'a> {
     pub fn get_pos_vector(&self, x: f32, y: f32) -> Vector {
@@ -67,7 +78,6 @@ fn main() {
 
 async fn app(window: Window, gfx: Graphics, events: EventStream) -> Result<()> {
     let context = Context::new([0., 0.].into());
-    let mut screen = Game::new().await;
     let mut wrapper = Wrapper {
         window,
         gfx,
@@ -76,13 +86,25 @@ async fn app(window: Window, gfx: Graphics, events: EventStream) -> Result<()> {
         last_cursor_pos: [0., 0.].into(),
         loaded_font: None,
         loaded_images: HashMap::new(),
+        settings: GameSettings::default(),
+        keymap: saves::read_keymap(),
+        data: GameData::default(),
+        ticks: 0,
     };
     wrapper.gfx.clear(Color::BLACK);
     wrapper.gfx.present(&wrapper.window)?;
+    let first_screen: Box<dyn Screen> = match assets::load_game_data().await {
+        Ok(data) => {
+            wrapper.data = data;
+            Box::new(MainMenu::new())
+        }
+        Err(error) => Box::new(ErrorScreen::new(error.to_string())),
+    };
+    let mut screens = ScreenStack::new(first_screen, &mut wrapper).await?;
     let mut has_focus = true;
     let mut draw_timer = Timer::time_per_second(60.);
     let mut update_timer = Timer::time_per_second(20.);
-    loop {
+    while !screens.is_empty() {
         while let Some(event) = wrapper.events.next_event().await {
             if let quicksilver::lifecycle::Event::FocusChanged(x) = &event {
                 has_focus = x.is_focused();
@@ -92,22 +114,23 @@ async fn app(window: Window, gfx: Graphics, events: EventStream) -> Result<()> {
                 if let quicksilver::lifecycle::Event::PointerMoved(x) = &event {
                     wrapper.last_cursor_pos = x.location();
                 }
-                screen.event(&mut wrapper, &event).await?;
+                screens.event(&mut wrapper, &event).await?;
             }
         }
         if has_focus {
             if update_timer.exhaust().is_some() {
-                screen.update(&mut wrapper).await?;
+                wrapper.ticks += 1;
+                screens.update(&mut wrapper).await?;
             }
             if draw_timer.exhaust().is_some() {
                 wrapper.gfx.clear(Color::BLACK);
-                screen.draw(&mut wrapper).await?;
+                screens.draw(&mut wrapper).await?;
                 wrapper.gfx.flush(None)?;
-                let translation = screen.translate;
-                //wrapper.gfx.set_transform(Transform::IDENTITY);
+                wrapper.gfx.set_transform(Transform::IDENTITY);
                 wrapper.context.render(&mut wrapper.gfx, &wrapper.window)?;
                 wrapper.gfx.present(&wrapper.window)?;
             }
         }
     }
+    Ok(())
 }
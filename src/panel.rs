@@ -1,4 +1,4 @@
-use crate::Wrapper;
+use crate::{input::Action, Wrapper};
 use mergui::{
     channels::{BasicClickable, Clickable},
     widgets::{button::Button, ButtonConfig},
@@ -13,6 +13,8 @@ use quicksilver::{
 
 pub struct Panel<T> {
     widgets: Vec<(T, ButtonConfig)>,
+    /// The option picked with the keyboard, if the keyboard got used.
+    highlighted: Option<usize>,
 }
 
 pub struct PanelConfig<T> {
@@ -51,19 +53,53 @@ impl<T> Panel<T> {
                         text,
                         font_style,
                         background: background.clone(),
-                        background_location: dbg!(Rectangle::new(
+                        background_location: Rectangle::new(
                             (top_left.x, height),
                             (width, text_size),
-                        )),
+                        ),
                         blend_color: None,
                         hover_color: None,
                     },
                 )
             })
             .collect::<Vec<_>>();
-        Self { widgets }
+        Self {
+            widgets,
+            highlighted: None,
+        }
+    }
+    pub fn highlight_next(&mut self) {
+        let len = self.widgets.len();
+        if len > 0 {
+            self.highlighted = Some(self.highlighted.map(|at| (at + 1) % len).unwrap_or(0));
+        }
+    }
+    pub fn highlight_previous(&mut self) {
+        let len = self.widgets.len();
+        if len > 0 {
+            self.highlighted = Some(self.highlighted.map(|at| (at + len - 1) % len).unwrap_or(0));
+        }
+    }
+    /// The highlighted option, which gets highlighted first if nothing was yet.
+    pub fn confirm(&mut self) -> Option<&T> {
+        if let None = self.highlighted {
+            self.highlight_next();
+            return None;
+        }
+        let at = self.highlighted?;
+        self.widgets.get(at).map(|(ret, _)| ret)
     }
     pub fn event(&mut self, wrapper: &mut Wrapper, event: &Event) -> Option<&T> {
+        match wrapper.keymap.action_of(event) {
+            Some((Action::CursorUp, true)) | Some((Action::PreviousUnit, true)) => {
+                self.highlight_previous()
+            }
+            Some((Action::CursorDown, true)) | Some((Action::NextUnit, true)) => {
+                self.highlight_next()
+            }
+            Some((Action::Confirm, true)) => return self.confirm(),
+            _ => {}
+        }
         if let Event::PointerInput(x) = event {
             if x.is_down() && x.button() == MouseButton::Left {
                 for (ret, config) in self.widgets.iter() {
@@ -76,7 +112,7 @@ impl<T> Panel<T> {
         None
     }
     pub fn draw(&self, translate: Vector, wrapper: &mut Wrapper) -> Result<()> {
-        for (_, config) in &self.widgets {
+        for (key, (_, config)) in self.widgets.iter().enumerate() {
             let translated =
                 Transform::translate(translate).inverse() * config.background_location.pos;
             let new_rec = Rectangle::new(translated, config.background_location.size);
@@ -86,6 +122,9 @@ impl<T> Panel<T> {
                 ..config.font_style.clone()
             };
             new_style.draw(&mut wrapper.gfx, &config.text)?;
+            if self.highlighted == Some(key) {
+                wrapper.gfx.stroke_rect(&new_rec, Color::RED);
+            }
         }
         Ok(())
     }
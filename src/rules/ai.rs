@@ -0,0 +1,311 @@
+use super::{
+    character::{within_range, Character, CharacterContainer},
+    combat,
+    grid::Grid,
+    random::Rng,
+};
+use mint::Vector2;
+use serde::{Deserialize, Serialize};
+
+/// How hard the AI tries to find a good move.
+#[derive(Serialize, Deserialize, Clone, Copy, Eq, PartialEq)]
+pub enum Difficulty {
+    /// Picks any move it can make.
+    Random,
+    /// Picks the move that scores best right now.
+    Greedy,
+    /// Plays every move out and subtracts the best attack the enemy has in reply.
+    Lookahead,
+}
+
+impl Difficulty {
+    pub fn next(self) -> Self {
+        match self {
+            Difficulty::Random => Difficulty::Greedy,
+            Difficulty::Greedy => Difficulty::Lookahead,
+            Difficulty::Lookahead => Difficulty::Random,
+        }
+    }
+    pub fn get_name(self) -> &'static str {
+        match self {
+            Difficulty::Random => "Easy",
+            Difficulty::Greedy => "Normal",
+            Difficulty::Lookahead => "Hard",
+        }
+    }
+}
+
+/// What the AI cares about when scoring moves.
+#[derive(Serialize, Deserialize, Clone, Copy, Eq, PartialEq, Default)]
+pub enum Personality {
+    /// Goes for the enemy and attacks whenever it can.
+    #[default]
+    Aggressive,
+    /// Holds good terrain and stays out of reach of the enemy.
+    Defensive,
+    /// Heads for tiles it has to reach and commanders it has to kill.
+    Objective,
+}
+
+struct Weights {
+    attack: f32,
+    closeness: f32,
+    defense: f32,
+    threat: f32,
+}
+
+impl Personality {
+    pub fn next(self) -> Self {
+        match self {
+            Personality::Aggressive => Personality::Defensive,
+            Personality::Defensive => Personality::Objective,
+            Personality::Objective => Personality::Aggressive,
+        }
+    }
+    pub fn get_name(self) -> &'static str {
+        match self {
+            Personality::Aggressive => "Aggressive",
+            Personality::Defensive => "Defensive",
+            Personality::Objective => "Objective",
+        }
+    }
+    fn get_weights(self) -> Weights {
+        match self {
+            Personality::Aggressive => Weights {
+                attack: 20.,
+                closeness: 0.5,
+                defense: 1.,
+                threat: 0.5,
+            },
+            Personality::Defensive => Weights {
+                attack: 10.,
+                closeness: 0.1,
+                defense: 3.,
+                threat: 1.5,
+            },
+            Personality::Objective => Weights {
+                attack: 10.,
+                closeness: 1.,
+                defense: 1.,
+                threat: 1.,
+            },
+        }
+    }
+}
+
+/// How the computer plays a team.
+#[derive(Serialize, Deserialize, Clone, Copy)]
+pub struct AiConfig {
+    pub difficulty: Difficulty,
+    #[serde(default)]
+    pub personality: Personality,
+}
+
+/// What the AI wants a single unit to do: walk along `path`, then attack `target` if there is one.
+pub struct Plan {
+    pub unit: u64,
+    pub path: Vec<Vector2<i32>>,
+    pub target: Option<u64>,
+}
+
+fn distance(from: Vector2<i32>, to: Vector2<i32>) -> i32 {
+    (from.x - to.x).abs() + (from.y - to.y).abs()
+}
+
+/// How good attacking `defender` from `from` is, counting what the counter attack would cost.
+fn score_attack(
+    attacker: &Character,
+    defender: &Character,
+    from: Vector2<i32>,
+    grid: &Grid,
+) -> f32 {
+    let preview = combat::preview(attacker, defender, grid);
+    let mut score = preview.damage as f32 * preview.hit_chance as f32 / 100.;
+    if preview.damage >= defender.hp {
+        score += 10.;
+    } else if within_range(defender.position, from, defender.char_type.get_range())
+        && grid.has_line_of_sight(defender.position, from)
+    {
+        let counter = combat::preview_at(defender, attacker, from, grid);
+        score -= counter.damage as f32 * counter.hit_chance as f32 / 200.;
+    }
+    if defender.commander {
+        score += 5.;
+    }
+    score
+}
+
+/// How good it is to end a move on `tile`: close to what it goes after and on good terrain.
+fn score_position(
+    tile: Vector2<i32>,
+    goals: &[Vector2<i32>],
+    weights: &Weights,
+    grid: &Grid,
+) -> f32 {
+    let closest = goals
+        .iter()
+        .map(|goal| distance(tile, *goal))
+        .min()
+        .unwrap_or(0);
+    let defense = grid.get(tile).map(|tile| tile.defense_bonus).unwrap_or(0);
+    defense as f32 * weights.defense - closest as f32 * weights.closeness
+}
+
+/// Plays a move out on a copy of the characters: `unit` walks to `tile` and hits `target` for full damage.
+fn simulate(
+    characters: &CharacterContainer,
+    grid: &Grid,
+    unit: u64,
+    tile: Vector2<i32>,
+    target: Option<u64>,
+) -> CharacterContainer {
+    let mut after = characters.clone();
+    if let Some(character) = after.characters.get_mut(&unit) {
+        character.position = tile;
+        character.has_moved = true;
+    }
+    if let Some(target) = target {
+        let damage = match (after.characters.get(&unit), after.characters.get(&target)) {
+            (Some(attacker), Some(defender)) => combat::preview(attacker, defender, grid).damage,
+            _ => 0,
+        };
+        if let Some(defender) = after.characters.get_mut(&target) {
+            defender.hp -= damage;
+        }
+        after.remove_dead();
+    }
+    after
+}
+
+/// How good the best attack is that the enemies of `team` can make on their next turn,
+/// scored the same way the AI scores its own attacks.
+fn best_response(characters: &CharacterContainer, grid: &Grid, team: usize) -> f32 {
+    let mut best = 0.;
+    for enemy in characters
+        .characters
+        .values()
+        .filter(|enemy| enemy.team != team)
+    {
+        for from in characters.get_reachable_tiles(enemy.id, grid).into_keys() {
+            for target in characters.characters.values().filter(|target| {
+                target.team == team
+                    && within_range(from, target.position, enemy.char_type.get_range())
+                    && grid.has_line_of_sight(from, target.position)
+            }) {
+                best = f32::max(best, score_attack(enemy, target, from, grid));
+            }
+        }
+    }
+    best
+}
+
+/// Picks a move for one of the team's units that hasn't moved yet.
+/// `objectives` are the tiles an objective focused AI heads for.
+/// Returns None once every unit of the team has acted.
+pub fn plan_move(
+    characters: &CharacterContainer,
+    grid: &Grid,
+    team: usize,
+    config: AiConfig,
+    objectives: &[Vector2<i32>],
+    rng: &mut Rng,
+) -> Option<Plan> {
+    let mut units = characters
+        .characters
+        .values()
+        .filter(|character| characters.can_move(character.id, team))
+        .collect::<Vec<_>>();
+    units.sort_by_key(|character| character.id);
+    let character = units.into_iter().next()?;
+    let weights = config.personality.get_weights();
+    let mut enemies = characters
+        .characters
+        .values()
+        .filter(|other| other.team != character.team)
+        .collect::<Vec<_>>();
+    enemies.sort_by_key(|enemy| enemy.id);
+    let goals = match config.personality {
+        Personality::Objective if !objectives.is_empty() => objectives.to_vec(),
+        _ => enemies.iter().map(|enemy| enemy.position).collect(),
+    };
+
+    // Sorted so the seeded rng and ties pick the same move every time, whatever the hash order.
+    let mut tiles = characters
+        .get_reachable_tiles(character.id, grid)
+        .into_keys()
+        .collect::<Vec<_>>();
+    tiles.sort_by_key(|tile| (tile.y, tile.x));
+    let mut options = Vec::new();
+    for tile in &tiles {
+        let score = score_position(*tile, &goals, &weights, grid);
+        options.push((score, *tile, None));
+        for enemy in enemies.iter().filter(|enemy| {
+            within_range(*tile, enemy.position, character.char_type.get_range())
+                && grid.has_line_of_sight(*tile, enemy.position)
+        }) {
+            options.push((
+                score + weights.attack + score_attack(character, enemy, *tile, grid),
+                *tile,
+                Some(enemy.id),
+            ));
+        }
+    }
+    if let Difficulty::Lookahead = config.difficulty {
+        for (score, tile, target) in &mut options {
+            let after = simulate(characters, grid, character.id, *tile, *target);
+            *score -= best_response(&after, grid, character.team) * weights.threat;
+        }
+    }
+    let (_, tile, target) = match config.difficulty {
+        Difficulty::Random if !options.is_empty() => {
+            let chosen = (rng.next_u64() % options.len() as u64) as usize;
+            options.swap_remove(chosen)
+        }
+        _ => options
+            .into_iter()
+            .max_by(|(a, _, _), (b, _, _)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))?,
+    };
+    let path = characters.find_path(character.id, character.position, tile, grid)?;
+    Some(Plan {
+        unit: character.id,
+        path,
+        target,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::test_data::{at, battle, map};
+
+    fn destination(difficulty: Difficulty) -> Vector2<i32> {
+        let battle = battle(map(
+            &["rrrrrrrrrr"],
+            &[(0, 0, "Soldier", 0), (7, 0, "Soldier", 1)],
+        ));
+        let config = AiConfig {
+            difficulty,
+            personality: Personality::Aggressive,
+        };
+        let plan = plan_move(
+            &battle.characters,
+            &battle.grid,
+            1,
+            config,
+            &[],
+            &mut Rng::new(0),
+        )
+        .unwrap();
+        *plan.path.last().unwrap()
+    }
+
+    #[test]
+    fn greedy_walks_as_close_as_it_can() {
+        assert_eq!(destination(Difficulty::Greedy), at(4, 0));
+    }
+
+    #[test]
+    fn lookahead_stays_out_of_the_enemys_reach() {
+        assert_eq!(destination(Difficulty::Lookahead), at(5, 0));
+    }
+}
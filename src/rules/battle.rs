@@ -0,0 +1,403 @@
+use super::{
+    character::{CharacterContainer, CharacterTypes},
+    combat::{self, Attack},
+    data::GameData,
+    error::MapError,
+    grid::{Grid, ParseableMap},
+    random::Rng,
+    spells::Spell,
+    turn::TurnManager,
+    victory::{check_victory, WinCondition},
+};
+use mint::Vector2;
+use std::{collections::HashMap, fmt};
+
+/// Something that happened because of an action.
+pub enum Event {
+    Moved {
+        unit: u64,
+        path: Vec<Vector2<i32>>,
+    },
+    Attacked(Attack),
+    Cast {
+        caster: u64,
+        spell: String,
+        target: Vector2<i32>,
+        affected: Vec<u64>,
+    },
+    Died {
+        unit: u64,
+        position: Vector2<i32>,
+    },
+    TurnStarted {
+        team: usize,
+        turn: u32,
+    },
+    Won {
+        team: usize,
+    },
+}
+
+/// Why an action was refused. Nothing changed when one of these is returned.
+#[derive(Debug)]
+pub enum ActionError {
+    BattleOver,
+    UnknownCharacter(u64),
+    NotYourTurn(u64),
+    AlreadyMoved(u64),
+    /// Only the character that just moved can attack or cast.
+    HasNotMoved(u64),
+    InvalidPath,
+    OutOfRange,
+    /// Characters can only attack characters of other teams.
+    NotAnEnemy(u64),
+    StillMoving,
+}
+
+impl fmt::Display for ActionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ActionError::BattleOver => write!(f, "The battle is already over"),
+            ActionError::UnknownCharacter(id) => write!(f, "There is no character {}", id),
+            ActionError::NotYourTurn(id) => write!(f, "Character {} can't act this turn", id),
+            ActionError::AlreadyMoved(id) => write!(f, "Character {} already moved", id),
+            ActionError::HasNotMoved(id) => write!(f, "Character {} has to move first", id),
+            ActionError::InvalidPath => write!(f, "The character can't walk that path"),
+            ActionError::OutOfRange => write!(f, "The target is out of range"),
+            ActionError::NotAnEnemy(id) => write!(f, "Character {} is not an enemy", id),
+            ActionError::StillMoving => write!(f, "A character is still moving"),
+        }
+    }
+}
+
+/// Everything about a battle that the rules care about.
+/// Changes happen through the actions, which check they are allowed and report what happened.
+pub struct Battle {
+    pub grid: Grid,
+    pub characters: CharacterContainer,
+    pub turns: TurnManager,
+    pub win_conditions: Vec<WinCondition>,
+    pub rng: Rng,
+}
+
+impl Battle {
+    pub fn new(map: ParseableMap, data: &GameData, seed: u64) -> Result<Self, MapError> {
+        let (grid, characters, turns, win_conditions) = map.parse(data)?;
+        Ok(Self {
+            grid,
+            characters,
+            turns,
+            win_conditions,
+            rng: Rng::new(seed),
+        })
+    }
+    pub fn winner(&self) -> Option<usize> {
+        check_victory(&self.win_conditions, &self.characters, &self.turns)
+    }
+    fn check_can_act(&self) -> Result<(), ActionError> {
+        if self.winner().is_some() {
+            return Err(ActionError::BattleOver);
+        }
+        if self.characters.is_moving() {
+            return Err(ActionError::StillMoving);
+        }
+        Ok(())
+    }
+    /// Checks that `id` just moved, so it may still attack or cast.
+    fn check_can_follow_up(&self, id: u64) -> Result<(), ActionError> {
+        self.check_can_act()?;
+        if !self.characters.characters.contains_key(&id) {
+            return Err(ActionError::UnknownCharacter(id));
+        }
+        if self.characters.get_last_moved() != Some(id) {
+            return Err(ActionError::HasNotMoved(id));
+        }
+        Ok(())
+    }
+    fn add_results(&self, mut events: Vec<Event>) -> Vec<Event> {
+        if let Some(team) = self.winner() {
+            events.push(Event::Won { team });
+        }
+        events
+    }
+
+    /// Every tile the character could walk to this turn, with what it costs to get there.
+    pub fn select(&self, id: u64) -> Result<HashMap<Vector2<i32>, usize>, ActionError> {
+        self.check_can_act()?;
+        let character = self
+            .characters
+            .characters
+            .get(&id)
+            .ok_or(ActionError::UnknownCharacter(id))?;
+        if character.team != self.turns.active_player {
+            return Err(ActionError::NotYourTurn(id));
+        }
+        if character.has_moved {
+            return Err(ActionError::AlreadyMoved(id));
+        }
+        Ok(self.characters.get_reachable_tiles(id, &self.grid))
+    }
+    /// Walks the character along `path`, which starts at the tile it stands on.
+    /// The move can be undone until the character attacks, casts or `finish_move` is called.
+    pub fn move_character(
+        &mut self,
+        id: u64,
+        path: Vec<Vector2<i32>>,
+    ) -> Result<Vec<Event>, ActionError> {
+        self.select(id)?;
+        let position = self.characters.characters[&id].position;
+        if path.first() != Some(&position) || !self.characters.can_take_path(id, &path, &self.grid)
+        {
+            return Err(ActionError::InvalidPath);
+        }
+        self.characters.finalize();
+        self.characters.move_character(id, path.clone());
+        Ok(vec![Event::Moved { unit: id, path }])
+    }
+    /// Lets the character that just moved attack `defender`, ending its move.
+    pub fn attack(&mut self, attacker: u64, defender: u64) -> Result<Vec<Event>, ActionError> {
+        self.check_can_follow_up(attacker)?;
+        let attacking_team = self.characters.characters[&attacker].team;
+        let defending_team = self
+            .characters
+            .characters
+            .get(&defender)
+            .ok_or(ActionError::UnknownCharacter(defender))?
+            .team;
+        if attacking_team == defending_team {
+            return Err(ActionError::NotAnEnemy(defender));
+        }
+        let result = combat::resolve_fight(
+            &mut self.characters,
+            &self.grid,
+            &mut self.rng,
+            attacker,
+            defender,
+        )
+        .ok_or(ActionError::OutOfRange)?;
+        self.characters.finalize();
+        let mut events = vec![Event::Attacked(result.attack)];
+        events.extend(result.counter.map(Event::Attacked));
+        events.extend(result.dead.iter().map(|character| Event::Died {
+            unit: character.id,
+            position: character.position,
+        }));
+        Ok(self.add_results(events))
+    }
+    /// Lets the character that just moved cast `spell` at `target`, ending its move.
+    pub fn cast(
+        &mut self,
+        caster: u64,
+        spell: &Spell,
+        target: Vector2<i32>,
+        types: &CharacterTypes,
+    ) -> Result<Vec<Event>, ActionError> {
+        self.check_can_follow_up(caster)?;
+        let result = spell
+            .cast(caster, target, &mut self.characters, &self.grid, types)
+            .ok_or(ActionError::OutOfRange)?;
+        self.characters.finalize();
+        let mut events = vec![Event::Cast {
+            caster,
+            spell: spell.name.clone(),
+            target,
+            affected: result.affected,
+        }];
+        events.extend(result.dead.iter().map(|character| Event::Died {
+            unit: character.id,
+            position: character.position,
+        }));
+        Ok(self.add_results(events))
+    }
+    /// Makes the last move final without doing anything else.
+    pub fn finish_move(&mut self) {
+        self.characters.finalize();
+    }
+    /// Puts the character that moved last back where it came from.
+    pub fn undo(&mut self) {
+        self.characters.undo();
+    }
+    pub fn end_turn(&mut self) -> Result<Vec<Event>, ActionError> {
+        self.check_can_act()?;
+        self.turns.end_turn(&mut self.characters);
+        let events = vec![Event::TurnStarted {
+            team: self.turns.active_player,
+            turn: self.turns.turn,
+        }];
+        Ok(self.add_results(events))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::test_data::{at, battle, finish_walking, map};
+
+    /// A soldier of each team at the ends of a road, with a second red soldier below the first.
+    fn skirmish() -> Battle {
+        battle(map(
+            &["rrrrr", "rrrrr"],
+            &[
+                (0, 0, "Soldier", 0),
+                (4, 0, "Soldier", 1),
+                (0, 1, "Soldier", 0),
+            ],
+        ))
+    }
+
+    #[test]
+    fn select_lists_the_reachable_tiles() {
+        let battle = skirmish();
+        let reachable = battle.select(0).unwrap();
+        assert_eq!(reachable.get(&at(0, 0)), Some(&0));
+        assert_eq!(reachable.get(&at(3, 0)), Some(&3));
+        assert_eq!(reachable.get(&at(0, 1)), None);
+        assert_eq!(reachable.get(&at(4, 0)), None);
+    }
+
+    #[test]
+    fn select_refuses_characters_that_can_not_act() {
+        let mut battle = skirmish();
+        assert!(matches!(
+            battle.select(9),
+            Err(ActionError::UnknownCharacter(9))
+        ));
+        assert!(matches!(battle.select(1), Err(ActionError::NotYourTurn(1))));
+        battle.move_character(0, vec![at(0, 0), at(1, 0)]).unwrap();
+        assert!(matches!(battle.select(2), Err(ActionError::StillMoving)));
+        finish_walking(&mut battle);
+        battle.finish_move();
+        assert!(matches!(
+            battle.select(0),
+            Err(ActionError::AlreadyMoved(0))
+        ));
+    }
+
+    #[test]
+    fn move_character_walks_the_path() {
+        let mut battle = skirmish();
+        let path = vec![at(0, 0), at(1, 0), at(2, 0)];
+        let events = battle.move_character(0, path.clone()).unwrap();
+        assert!(matches!(&events[..], [Event::Moved { unit: 0, path: walked }] if *walked == path));
+        let character = &battle.characters.characters[&0];
+        assert_eq!(character.position, at(2, 0));
+        assert!(character.has_moved);
+        assert_eq!(battle.characters.get_last_moved(), Some(0));
+    }
+
+    #[test]
+    fn move_character_allows_staying_in_place() {
+        let mut battle = skirmish();
+        battle.move_character(0, vec![at(0, 0)]).unwrap();
+        assert_eq!(battle.characters.characters[&0].position, at(0, 0));
+        assert_eq!(battle.characters.get_last_moved(), Some(0));
+    }
+
+    #[test]
+    fn move_character_refuses_invalid_paths() {
+        let mut battle = skirmish();
+        let not_from_start = vec![at(1, 0), at(2, 0)];
+        let too_long = vec![at(0, 0), at(1, 0), at(2, 0), at(3, 0), at(3, 1)];
+        let through_ally = vec![at(0, 0), at(0, 1), at(1, 1)];
+        for path in vec![not_from_start, too_long, through_ally] {
+            assert!(matches!(
+                battle.move_character(0, path),
+                Err(ActionError::InvalidPath)
+            ));
+        }
+        let character = &battle.characters.characters[&0];
+        assert_eq!(character.position, at(0, 0));
+        assert!(!character.has_moved);
+    }
+
+    #[test]
+    fn attack_damages_both_sides() {
+        let mut battle = skirmish();
+        battle
+            .move_character(0, vec![at(0, 0), at(1, 0), at(2, 0), at(3, 0)])
+            .unwrap();
+        finish_walking(&mut battle);
+        let events = battle.attack(0, 1).unwrap();
+        assert!(matches!(
+            &events[..],
+            [
+                Event::Attacked(Attack {
+                    attacker: 0,
+                    defender: 1,
+                    hit: true,
+                    damage: 3,
+                    killed: false,
+                }),
+                Event::Attacked(Attack {
+                    attacker: 1,
+                    defender: 0,
+                    hit: true,
+                    damage: 3,
+                    killed: false,
+                }),
+            ]
+        ));
+        assert_eq!(battle.characters.characters[&0].hp, 7);
+        assert_eq!(battle.characters.characters[&1].hp, 7);
+        assert!(matches!(
+            battle.attack(0, 1),
+            Err(ActionError::HasNotMoved(0))
+        ));
+    }
+
+    #[test]
+    fn attack_refuses_invalid_targets() {
+        let mut battle = skirmish();
+        assert!(matches!(
+            battle.attack(0, 1),
+            Err(ActionError::HasNotMoved(0))
+        ));
+        battle.move_character(0, vec![at(0, 0), at(1, 0)]).unwrap();
+        assert!(matches!(battle.attack(0, 1), Err(ActionError::StillMoving)));
+        finish_walking(&mut battle);
+        assert!(matches!(
+            battle.attack(0, 9),
+            Err(ActionError::UnknownCharacter(9))
+        ));
+        assert!(matches!(
+            battle.attack(0, 0),
+            Err(ActionError::NotAnEnemy(0))
+        ));
+        assert!(matches!(
+            battle.attack(0, 2),
+            Err(ActionError::NotAnEnemy(2))
+        ));
+        assert!(matches!(battle.attack(0, 1), Err(ActionError::OutOfRange)));
+        assert_eq!(battle.characters.characters[&0].hp, 10);
+        assert_eq!(battle.characters.characters[&2].hp, 10);
+        assert_eq!(battle.characters.get_last_moved(), Some(0));
+    }
+
+    #[test]
+    fn end_turn_hands_the_turn_to_the_next_player() {
+        let mut battle = skirmish();
+        battle.move_character(0, vec![at(0, 0), at(1, 0)]).unwrap();
+        finish_walking(&mut battle);
+        let events = battle.end_turn().unwrap();
+        assert!(matches!(
+            &events[..],
+            [Event::TurnStarted { team: 1, turn: 1 }]
+        ));
+        assert!(battle.select(1).is_ok());
+        assert!(matches!(battle.select(0), Err(ActionError::NotYourTurn(0))));
+        let events = battle.end_turn().unwrap();
+        assert!(matches!(
+            &events[..],
+            [Event::TurnStarted { team: 0, turn: 2 }]
+        ));
+        assert!(battle.select(0).is_ok());
+    }
+
+    #[test]
+    fn actions_are_refused_once_the_battle_is_over() {
+        let mut battle = battle(map(&["rrr"], &[(0, 0, "Soldier", 0)]));
+        assert_eq!(battle.winner(), Some(0));
+        assert!(matches!(battle.select(0), Err(ActionError::BattleOver)));
+        assert!(matches!(battle.end_turn(), Err(ActionError::BattleOver)));
+    }
+}
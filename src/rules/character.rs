@@ -0,0 +1,499 @@
+use super::{
+    error::MapError,
+    grid::{Grid, ParseableMap},
+    pathfinding,
+};
+use mint::Vector2;
+use serde::{Deserialize, Serialize};
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, VecDeque},
+};
+#[derive(Deserialize, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum MovementClass {
+    Ground,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct CharacterType {
+    pub name: String,
+    walk_range: usize,
+    attack_range: i32,
+    hp: i32,
+    attack: i32,
+    defense: i32,
+    movement_class: MovementClass,
+    pub sprite: Option<String>,
+    #[serde(default)]
+    mana: i32,
+    #[serde(default)]
+    mana_regen: i32,
+    #[serde(default)]
+    pub spells: Vec<String>,
+}
+impl CharacterType {
+    pub fn get_range(&self) -> i32 {
+        self.attack_range
+    }
+    pub fn get_walk_range(&self) -> usize {
+        self.walk_range
+    }
+    pub fn get_movement_class(&self) -> MovementClass {
+        self.movement_class
+    }
+    pub fn get_max_hp(&self) -> i32 {
+        self.hp
+    }
+    pub fn get_attack(&self) -> i32 {
+        self.attack
+    }
+    pub fn get_defense(&self) -> i32 {
+        self.defense
+    }
+    pub fn get_max_mana(&self) -> i32 {
+        self.mana
+    }
+    pub fn get_mana_regen(&self) -> i32 {
+        self.mana_regen
+    }
+}
+
+/// Every character type defined in `units.json`, by name.
+#[derive(Default)]
+pub struct CharacterTypes {
+    types: HashMap<String, CharacterType>,
+}
+impl CharacterTypes {
+    /// Reads the types from the contents of `units.json`.
+    pub fn from_json(file: &[u8]) -> Result<Self, MapError> {
+        let types = serde_json::from_slice::<Vec<CharacterType>>(file)?
+            .into_iter()
+            .map(|char_type| (char_type.name.clone(), char_type))
+            .collect();
+        Ok(Self { types })
+    }
+    pub fn get(&self, name: &str) -> Option<&CharacterType> {
+        self.types.get(name)
+    }
+    pub fn sprites(&self) -> impl Iterator<Item = &String> {
+        self.types
+            .values()
+            .filter_map(|char_type| char_type.sprite.as_ref())
+    }
+}
+
+/// Whether `to` is close enough to `from` to be hit by something with the given range.
+pub fn within_range(from: Vector2<i32>, to: Vector2<i32>, range: i32) -> bool {
+    let (x, y) = ((to.x - from.x) as f32, (to.y - from.y) as f32);
+    (x * x + y * y).sqrt().ceil() < (range as f32)
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Buff {
+    pub attack: i32,
+    pub defense: i32,
+    pub turns_left: u32,
+}
+
+#[derive(Clone)]
+pub struct Character {
+    pub position: Vector2<i32>,
+    pub id: u64,
+    pub char_type: CharacterType,
+    pub has_moved: bool,
+    pub hp: i32,
+    pub team: usize,
+    pub commander: bool,
+    pub mana: i32,
+    pub buffs: Vec<Buff>,
+}
+impl Character {
+    pub fn new(
+        id: u64,
+        position: Vector2<i32>,
+        char_type: CharacterType,
+        team: usize,
+        commander: bool,
+    ) -> Self {
+        Self {
+            position,
+            id,
+            has_moved: false,
+            hp: char_type.get_max_hp(),
+            mana: char_type.get_max_mana(),
+            buffs: Vec::new(),
+            char_type,
+            team,
+            commander,
+        }
+    }
+    pub fn is_in_range(&self, position: Vector2<i32>) -> bool {
+        within_range(self.position, position, self.char_type.get_range())
+    }
+    pub fn get_attack(&self) -> i32 {
+        self.char_type.get_attack() + self.buffs.iter().map(|buff| buff.attack).sum::<i32>()
+    }
+    pub fn get_defense(&self) -> i32 {
+        self.char_type.get_defense() + self.buffs.iter().map(|buff| buff.defense).sum::<i32>()
+    }
+    fn start_turn(&mut self) {
+        self.has_moved = false;
+        self.mana =
+            (self.mana + self.char_type.get_mana_regen()).min(self.char_type.get_max_mana());
+        self.buffs
+            .iter_mut()
+            .for_each(|buff| buff.turns_left = buff.turns_left.saturating_sub(1));
+        self.buffs.retain(|buff| buff.turns_left > 0);
+    }
+    /// Whether this character can hit something standing on `position`.
+    pub fn can_attack(&self, position: Vector2<i32>, grid: &Grid) -> bool {
+        self.is_in_range(position) && grid.has_line_of_sight(self.position, position)
+    }
+    pub fn is_dead(&self) -> bool {
+        self.hp <= 0
+    }
+}
+
+/// What is left of a character after it died, until it decays.
+#[derive(Clone)]
+pub struct Corpse {
+    pub team: usize,
+    /// How many more turns of its team the corpse stays around.
+    pub turns_left: u32,
+}
+
+#[derive(Clone)]
+pub struct CharacterContainer {
+    pub next_id: u64,
+    pub characters: HashMap<u64, Character>,
+    pub corpses: HashMap<Vector2<i32>, Corpse>,
+    corpse_decay: u32,
+    pub path: Option<(u64, VecDeque<Vector2<i32>>)>,
+    last_move: Option<(u64, Vector2<i32>)>,
+}
+impl CharacterContainer {
+    pub fn new(map: &ParseableMap, types: &CharacterTypes) -> Result<Self, MapError> {
+        let mut next_id = 0;
+
+        let mut characters = HashMap::new();
+        for character in &map.characters {
+            let loc = [character.x, character.y].into();
+            let char_type = types.get(&character.char_type).cloned().ok_or_else(|| {
+                MapError::UnknownCharacterType {
+                    name: character.char_type.clone(),
+                }
+            })?;
+            characters.insert(
+                next_id,
+                Character::new(next_id, loc, char_type, character.team, character.commander),
+            );
+            next_id += 1
+        }
+        Ok(Self {
+            characters,
+            next_id,
+            corpses: HashMap::new(),
+            corpse_decay: map.corpse_decay,
+            path: None,
+            last_move: None,
+        })
+    }
+
+    /// Sums the cost of every tile the path enters, the first tile being the one the character stands on.
+    /// Returns None if the path goes over a tile the character can't enter.
+    pub fn get_path_cost(&self, id: u64, path: &[Vector2<i32>], grid: &Grid) -> Option<usize> {
+        let class = self.characters.get(&id)?.char_type.get_movement_class();
+        path.iter().skip(1).try_fold(0, |total, pos| {
+            self.get_tile_cost(id, class, *pos, grid)
+                .map(|cost| total + cost)
+        })
+    }
+
+    /// Whether the character could end up on `pos`, ignoring how far away it is.
+    pub fn can_stand_on(&self, id: u64, pos: Vector2<i32>, grid: &Grid) -> bool {
+        self.characters
+            .get(&id)
+            .and_then(|character| {
+                self.get_tile_cost(id, character.char_type.get_movement_class(), pos, grid)
+            })
+            .is_some()
+    }
+
+    fn get_tile_cost(
+        &self,
+        id: u64,
+        class: MovementClass,
+        pos: Vector2<i32>,
+        grid: &Grid,
+    ) -> Option<usize> {
+        match self.get_char_id_by_pos(pos) {
+            Some(other) if other != id => None,
+            _ => grid.get(pos)?.get_move_cost(class),
+        }
+    }
+
+    /// The cheapest path from `from` to `to` for the given character, ignoring its walk range.
+    pub fn find_path(
+        &self,
+        id: u64,
+        from: Vector2<i32>,
+        to: Vector2<i32>,
+        grid: &Grid,
+    ) -> Option<Vec<Vector2<i32>>> {
+        let class = self.characters.get(&id)?.char_type.get_movement_class();
+        pathfinding::find_path(grid, from, to, |pos| {
+            self.get_tile_cost(id, class, pos, grid)
+        })
+    }
+
+    pub fn can_take_path(&self, id: u64, path: &[Vector2<i32>], grid: &Grid) -> bool {
+        let walk_range = self
+            .characters
+            .get(&id)
+            .expect("NO!")
+            .char_type
+            .get_walk_range();
+        self.get_path_cost(id, path, grid)
+            .map(|cost| cost <= walk_range)
+            .unwrap_or(false)
+    }
+
+    /// Every tile the character can reach this turn together with the cheapest cost to get there.
+    pub fn get_reachable_tiles(&self, id: u64, grid: &Grid) -> HashMap<Vector2<i32>, usize> {
+        let mut reachable = HashMap::new();
+        let character = match self.characters.get(&id) {
+            Some(x) => x,
+            None => return reachable,
+        };
+        let walk_range = character.char_type.get_walk_range();
+        let class = character.char_type.get_movement_class();
+        let mut to_visit = BinaryHeap::new();
+        to_visit.push(Reverse((0, character.position.x, character.position.y)));
+        while let Some(Reverse((cost, x, y))) = to_visit.pop() {
+            let pos: Vector2<i32> = [x, y].into();
+            if reachable.contains_key(&pos) {
+                continue;
+            }
+            reachable.insert(pos, cost);
+            for next in grid.neighbors(pos) {
+                if reachable.contains_key(&next) {
+                    continue;
+                }
+                let next_cost = self
+                    .get_tile_cost(id, class, next, grid)
+                    .map(|tile_cost| cost + tile_cost);
+                if let Some(next_cost) = next_cost {
+                    if next_cost <= walk_range {
+                        to_visit.push(Reverse((next_cost, next.x, next.y)));
+                    }
+                }
+            }
+        }
+        reachable
+    }
+
+    pub fn finalize(&mut self) {
+        self.last_move = None;
+    }
+    pub fn can_move(&self, id: u64, active_team: usize) -> bool {
+        self.characters
+            .get(&id)
+            .map(|v| !v.has_moved && v.team == active_team)
+            .unwrap_or(false)
+    }
+    pub fn start_turn(&mut self, team: usize) {
+        self.finalize();
+        self.characters
+            .values_mut()
+            .filter(|character| character.team == team)
+            .for_each(Character::start_turn);
+        self.corpses
+            .values_mut()
+            .filter(|corpse| corpse.team == team)
+            .for_each(|corpse| corpse.turns_left = corpse.turns_left.saturating_sub(1));
+        self.corpses.retain(|_, corpse| corpse.turns_left > 0);
+    }
+    /// Builds a container from characters and corpses that were already on the field, like those from a save.
+    pub fn restore(
+        characters: HashMap<u64, Character>,
+        corpses: HashMap<Vector2<i32>, Corpse>,
+        next_id: u64,
+        corpse_decay: u32,
+    ) -> Self {
+        Self {
+            next_id,
+            characters,
+            corpses,
+            corpse_decay,
+            path: None,
+            last_move: None,
+        }
+    }
+    pub fn get_corpse_decay(&self) -> u32 {
+        self.corpse_decay
+    }
+    /// The character that moved last, as long as that move can still be undone.
+    pub fn get_last_moved(&self) -> Option<u64> {
+        self.last_move.map(|(id, _)| id)
+    }
+    pub fn undo(&mut self) {
+        self.path = None;
+        if let Some((id, loc)) = self.last_move {
+            if let Some(v) = self.characters.get_mut(&id) {
+                v.position = loc;
+                v.has_moved = false;
+            };
+        }
+        self.finalize();
+    }
+
+    pub fn get_char_ids_in_range_of(&self, id: u64, grid: &Grid) -> Vec<(u64, Vector2<i32>)> {
+        if let Some(attacker) = self.characters.get(&id) {
+            self.characters
+                .iter()
+                .filter(|(_, character)| {
+                    character.team != attacker.team && attacker.can_attack(character.position, grid)
+                })
+                .map(|(id, character)| (*id, character.position))
+                .collect()
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Removes every dead character, leaving a corpse where it stood.
+    pub fn remove_dead(&mut self) -> Vec<Character> {
+        let dead = self
+            .characters
+            .iter()
+            .filter(|(_, character)| character.is_dead())
+            .map(|(id, _)| *id)
+            .collect::<Vec<_>>();
+        let dead = dead
+            .into_iter()
+            .filter_map(|id| self.characters.remove(&id))
+            .collect::<Vec<_>>();
+        for character in &dead {
+            if self.corpse_decay > 0 {
+                self.corpses.insert(
+                    character.position,
+                    Corpse {
+                        team: character.team,
+                        turns_left: self.corpse_decay,
+                    },
+                );
+            }
+        }
+        dead
+    }
+    /// Turns the corpse at `position` into a new character of the given team.
+    /// The raised character has to wait for the next turn before it can act.
+    pub fn raise_corpse(
+        &mut self,
+        position: Vector2<i32>,
+        char_type: CharacterType,
+        team: usize,
+    ) -> Option<u64> {
+        if self.get_char_id_by_pos(position).is_some() {
+            return None;
+        }
+        self.corpses.remove(&position)?;
+        let id = self.next_id;
+        self.next_id += 1;
+        let mut character = Character::new(id, position, char_type, team, false);
+        character.has_moved = true;
+        self.characters.insert(id, character);
+        Some(id)
+    }
+
+    pub fn get_char_id_by_pos(&self, position: Vector2<i32>) -> Option<u64> {
+        self.characters
+            .iter()
+            .find(|(_, character)| character.position == position)
+            .map(|(v, _)| *v)
+    }
+    pub fn move_character(&mut self, id: u64, path: Vec<Vector2<i32>>) {
+        if let Some(_) = &self.path {
+            return;
+        }
+        let character = self
+            .characters
+            .get_mut(&id)
+            .expect(&format!("id {} not found", id));
+        character.has_moved = true;
+        self.last_move = Some((id, character.position));
+        if let Some(end) = path.last() {
+            character.position = *end;
+        }
+        self.path = Some((id, path.into_iter().collect()))
+    }
+    pub fn is_moving(&self) -> bool {
+        self.path.is_some()
+    }
+    /// Where the character should be drawn, which lags behind its position while it walks there.
+    pub fn get_drawn_position(&self, character: &Character) -> Vector2<i32> {
+        match &self.path {
+            Some((id, path)) if *id == character.id => {
+                path.front().copied().unwrap_or(character.position)
+            }
+            _ => character.position,
+        }
+    }
+    /// Advances the walking animation by a step, returns true when it just finished.
+    pub fn update(&mut self) -> bool {
+        if let Some((_, path)) = &mut self.path {
+            path.pop_front();
+            if path.is_empty() {
+                self.path = None;
+                return true;
+            }
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::test_data::{at, battle, map};
+
+    #[test]
+    fn reachable_tiles_pay_for_terrain() {
+        let battle = battle(map(&["rfrr", "rrrw"], &[(0, 0, "Soldier", 0)]));
+        let reachable = battle.characters.get_reachable_tiles(0, &battle.grid);
+        let expected = vec![
+            (at(0, 0), 0),
+            (at(1, 0), 2),
+            (at(2, 0), 3),
+            (at(0, 1), 1),
+            (at(1, 1), 2),
+            (at(2, 1), 3),
+        ];
+        assert_eq!(reachable, expected.into_iter().collect());
+    }
+
+    #[test]
+    fn reachable_tiles_skip_blocked_tiles() {
+        let battle = battle(map(
+            &["rfrr", "rrrw"],
+            &[(0, 0, "Soldier", 0), (1, 1, "Soldier", 1)],
+        ));
+        let reachable = battle.characters.get_reachable_tiles(0, &battle.grid);
+        let expected = vec![(at(0, 0), 0), (at(1, 0), 2), (at(2, 0), 3), (at(0, 1), 1)];
+        assert_eq!(reachable, expected.into_iter().collect());
+    }
+
+    #[test]
+    fn buffs_without_turns_run_out() {
+        let mut battle = battle(map(&["rr"], &[(0, 0, "Soldier", 0)]));
+        let character = battle.characters.characters.get_mut(&0).unwrap();
+        character.buffs.push(Buff {
+            attack: 2,
+            defense: 0,
+            turns_left: 0,
+        });
+        assert_eq!(character.get_attack(), 7);
+        battle.characters.start_turn(0);
+        assert_eq!(battle.characters.characters[&0].get_attack(), 5);
+    }
+}
@@ -0,0 +1,172 @@
+use super::{
+    character::{Character, CharacterContainer},
+    grid::Grid,
+    random::Rng,
+};
+use mint::Vector2;
+
+const BASE_HIT_CHANCE: i32 = 90;
+
+pub struct Attack {
+    pub attacker: u64,
+    pub defender: u64,
+    pub hit: bool,
+    pub damage: i32,
+    pub killed: bool,
+}
+
+/// What an attack would do, taking the terrain the defender stands on into account.
+pub struct FightPreview {
+    pub damage: i32,
+    pub hit_chance: i32,
+    pub defense_bonus: i32,
+}
+
+pub struct FightResult {
+    pub attack: Attack,
+    pub counter: Option<Attack>,
+    pub dead: Vec<Character>,
+}
+
+pub fn preview(attacker: &Character, defender: &Character, grid: &Grid) -> FightPreview {
+    preview_at(attacker, defender, defender.position, grid)
+}
+
+/// Like `preview`, but with the defender standing on `position` instead of where it is now.
+pub fn preview_at(
+    attacker: &Character,
+    defender: &Character,
+    position: Vector2<i32>,
+    grid: &Grid,
+) -> FightPreview {
+    let (defense_bonus, evasion) = grid
+        .get(position)
+        .map(|tile| (tile.defense_bonus, tile.evasion))
+        .unwrap_or((0, 0));
+    FightPreview {
+        damage: (attacker.get_attack() - defender.get_defense() - defense_bonus).max(1),
+        hit_chance: (BASE_HIT_CHANCE - evasion).clamp(0, 100),
+        defense_bonus,
+    }
+}
+
+fn strike(
+    characters: &mut CharacterContainer,
+    grid: &Grid,
+    rng: &mut Rng,
+    attacker: u64,
+    defender: u64,
+) -> Option<Attack> {
+    let preview = preview(
+        characters.characters.get(&attacker)?,
+        characters.characters.get(&defender)?,
+        grid,
+    );
+    let hit = rng.chance(preview.hit_chance);
+    let damage = if hit { preview.damage } else { 0 };
+    let target = characters.characters.get_mut(&defender)?;
+    target.hp -= damage;
+    Some(Attack {
+        attacker,
+        defender,
+        hit,
+        damage,
+        killed: target.is_dead(),
+    })
+}
+
+/// Lets `attacker` hit `defender`, who hits back if it survives and the attacker is in its range.
+/// Characters that died are removed from the container and returned as part of the result.
+/// Nothing happens if the two are on the same team.
+pub fn resolve_fight(
+    characters: &mut CharacterContainer,
+    grid: &Grid,
+    rng: &mut Rng,
+    attacker: u64,
+    defender: u64,
+) -> Option<FightResult> {
+    let attacker_char = characters.characters.get(&attacker)?;
+    let defender_char = characters.characters.get(&defender)?;
+    let (attacker_pos, defender_pos) = (attacker_char.position, defender_char.position);
+    if attacker_char.team == defender_char.team || !attacker_char.can_attack(defender_pos, grid) {
+        return None;
+    }
+    let attack = strike(characters, grid, rng, attacker, defender)?;
+    let counter = if !attack.killed
+        && characters
+            .characters
+            .get(&defender)?
+            .can_attack(attacker_pos, grid)
+    {
+        strike(characters, grid, rng, defender, attacker)
+    } else {
+        None
+    };
+    let dead = characters.remove_dead();
+    Some(FightResult {
+        attack,
+        counter,
+        dead,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::{
+        character::Buff,
+        test_data::{at, battle, map},
+    };
+
+    #[test]
+    fn preview_on_open_ground() {
+        let battle = battle(map(&["rr"], &[(0, 0, "Soldier", 0), (1, 0, "Soldier", 1)]));
+        let characters = &battle.characters.characters;
+        let attack = preview(&characters[&0], &characters[&1], &battle.grid);
+        assert_eq!(attack.damage, 3);
+        assert_eq!(attack.hit_chance, 100);
+        assert_eq!(attack.defense_bonus, 0);
+    }
+
+    #[test]
+    fn preview_counts_the_defenders_terrain() {
+        let battle = battle(map(&["rf"], &[(0, 0, "Soldier", 0), (1, 0, "Soldier", 1)]));
+        let characters = &battle.characters.characters;
+        let attack = preview(&characters[&0], &characters[&1], &battle.grid);
+        assert_eq!(attack.damage, 2);
+        assert_eq!(attack.hit_chance, 70);
+        assert_eq!(attack.defense_bonus, 1);
+        let counter = preview(&characters[&1], &characters[&0], &battle.grid);
+        assert_eq!(counter.damage, 3);
+        assert_eq!(counter.hit_chance, 100);
+        assert_eq!(counter.defense_bonus, 0);
+    }
+
+    #[test]
+    fn preview_at_uses_the_given_tile() {
+        let battle = battle(map(&["rf"], &[(0, 0, "Soldier", 0), (1, 0, "Soldier", 1)]));
+        let characters = &battle.characters.characters;
+        let attack = preview_at(&characters[&1], &characters[&0], at(1, 0), &battle.grid);
+        assert_eq!(attack.damage, 2);
+        assert_eq!(attack.hit_chance, 70);
+    }
+
+    #[test]
+    fn preview_always_does_some_damage() {
+        let mut battle = battle(map(&["rf"], &[(0, 0, "Archer", 0), (1, 0, "Soldier", 1)]));
+        battle
+            .characters
+            .characters
+            .get_mut(&1)
+            .unwrap()
+            .buffs
+            .push(Buff {
+                attack: 0,
+                defense: 5,
+                turns_left: 1,
+            });
+        let characters = &battle.characters.characters;
+        let attack = preview(&characters[&0], &characters[&1], &battle.grid);
+        assert_eq!(attack.damage, 1);
+    }
+}
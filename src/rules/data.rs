@@ -0,0 +1,15 @@
+use super::{character::CharacterTypes, grid::TerrainTypes, spells::Spells};
+
+/// Everything that gets loaded from the static folder once at startup.
+#[derive(Default)]
+pub struct GameData {
+    pub character_types: CharacterTypes,
+    pub terrain: TerrainTypes,
+    pub spells: Spells,
+}
+
+impl GameData {
+    pub fn sprites(&self) -> impl Iterator<Item = &String> {
+        self.character_types.sprites().chain(self.terrain.sprites())
+    }
+}
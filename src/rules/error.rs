@@ -0,0 +1,118 @@
+use std::fmt;
+
+#[derive(Debug)]
+pub enum MapError {
+    CouldNotLoad(String),
+    Json(serde_json::Error),
+    EmptyMap,
+    NoPlayers,
+    RaggedRow {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+    UnknownTile {
+        row: usize,
+        column: usize,
+        character: char,
+    },
+    CharacterOutOfBounds {
+        x: i32,
+        y: i32,
+    },
+    CharactersOnSameTile {
+        x: i32,
+        y: i32,
+    },
+    UnknownTeam {
+        team: usize,
+    },
+    UnknownCharacterType {
+        name: String,
+    },
+    DuplicateTerrain {
+        character: char,
+    },
+    FreeTerrain {
+        name: String,
+    },
+    DuplicateCharacterId {
+        id: u64,
+    },
+    NextIdInUse {
+        next_id: u64,
+    },
+    InvalidHp {
+        id: u64,
+        hp: i32,
+    },
+    CorpseOutOfBounds {
+        x: i32,
+        y: i32,
+    },
+}
+
+impl fmt::Display for MapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MapError::CouldNotLoad(reason) => write!(f, "Could not load the map: {}", reason),
+            MapError::Json(error) => write!(f, "The map is not valid json: {}", error),
+            MapError::EmptyMap => write!(f, "The map has no tiles"),
+            MapError::NoPlayers => write!(f, "The map has no players"),
+            MapError::RaggedRow {
+                row,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Row {} has {} tiles while {} were expected",
+                row, found, expected
+            ),
+            MapError::UnknownTile {
+                row,
+                column,
+                character,
+            } => write!(
+                f,
+                "Unknown tile '{}' at row {}, column {}",
+                character, row, column
+            ),
+            MapError::CharacterOutOfBounds { x, y } => {
+                write!(f, "A character is placed outside the map at {}, {}", x, y)
+            }
+            MapError::CharactersOnSameTile { x, y } => {
+                write!(f, "Multiple characters are placed at {}, {}", x, y)
+            }
+            MapError::UnknownTeam { team } => {
+                write!(f, "Team {} is used but has no player", team)
+            }
+            MapError::UnknownCharacterType { name } => {
+                write!(f, "There is no character type called {}", name)
+            }
+            MapError::DuplicateTerrain { character } => {
+                write!(f, "Multiple terrains use the character '{}'", character)
+            }
+            MapError::FreeTerrain { name } => {
+                write!(f, "Entering {} has to cost at least 1", name)
+            }
+            MapError::DuplicateCharacterId { id } => {
+                write!(f, "Multiple characters have id {}", id)
+            }
+            MapError::NextIdInUse { next_id } => {
+                write!(f, "The next character id {} is already in use", next_id)
+            }
+            MapError::InvalidHp { id, hp } => {
+                write!(f, "Character {} can't have {} hp", id, hp)
+            }
+            MapError::CorpseOutOfBounds { x, y } => {
+                write!(f, "A corpse lies outside the map at {}, {}", x, y)
+            }
+        }
+    }
+}
+
+impl From<serde_json::Error> for MapError {
+    fn from(error: serde_json::Error) -> Self {
+        MapError::Json(error)
+    }
+}
@@ -0,0 +1,369 @@
+use super::{
+    character::{CharacterContainer, MovementClass},
+    data::GameData,
+    error::MapError,
+    turn::{Player, TurnManager},
+    victory::{default_win_conditions, WinCondition},
+};
+use mint::Vector2;
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+
+#[derive(Deserialize, Clone)]
+pub struct Terrain {
+    pub character: char,
+    pub name: String,
+    pub color: [u8; 3],
+    pub sprite: Option<String>,
+    move_costs: HashMap<MovementClass, usize>,
+    #[serde(default)]
+    pub defense_bonus: i32,
+    /// Percentage points taken off the chance to hit a character standing here.
+    #[serde(default)]
+    pub evasion: i32,
+    #[serde(default)]
+    pub blocks_line_of_sight: bool,
+}
+
+impl Terrain {
+    /// How much of a unit's walk range it costs to enter a tile of this type.
+    /// Returns None if the tile can not be entered at all.
+    pub fn get_move_cost(&self, class: MovementClass) -> Option<usize> {
+        self.move_costs.get(&class).copied()
+    }
+}
+
+/// Every terrain defined in `terrain.json`.
+#[derive(Default, Clone)]
+pub struct TerrainTypes {
+    terrains: Vec<Terrain>,
+}
+
+impl TerrainTypes {
+    /// Reads the terrain from the contents of `terrain.json`.
+    /// Every terrain needs its own map character and entering a tile has to cost something,
+    /// as pathfinding relies on that.
+    pub fn from_json(file: &[u8]) -> Result<Self, MapError> {
+        let terrains: Vec<Terrain> = serde_json::from_slice(file)?;
+        let mut seen = HashSet::new();
+        for terrain in &terrains {
+            if !seen.insert(terrain.character.to_ascii_lowercase()) {
+                return Err(MapError::DuplicateTerrain {
+                    character: terrain.character,
+                });
+            }
+            if terrain.move_costs.values().any(|cost| *cost == 0) {
+                return Err(MapError::FreeTerrain {
+                    name: terrain.name.clone(),
+                });
+            }
+        }
+        Ok(Self { terrains })
+    }
+    fn index_of(&self, character: char) -> Option<usize> {
+        let character = character.to_ascii_lowercase();
+        self.terrains
+            .iter()
+            .position(|terrain| terrain.character.to_ascii_lowercase() == character)
+    }
+    pub fn sprites(&self) -> impl Iterator<Item = &String> {
+        self.terrains
+            .iter()
+            .filter_map(|terrain| terrain.sprite.as_ref())
+    }
+}
+
+pub const TILE_SIZE: i32 = 64;
+/// The terrain of a map, stored row by row.
+pub struct Grid {
+    terrain: TerrainTypes,
+    tiles: Vec<usize>,
+    width: i32,
+    height: i32,
+}
+impl Grid {
+    pub fn new(
+        width: usize,
+        height: usize,
+        rows: &[String],
+        terrain: &TerrainTypes,
+    ) -> Result<Self, MapError> {
+        let mut tiles = Vec::with_capacity(width * height);
+        for (y, row) in rows.iter().enumerate().take(height) {
+            let row = row.chars().collect::<Vec<_>>();
+            if row.len() != width {
+                return Err(MapError::RaggedRow {
+                    row: y,
+                    expected: width,
+                    found: row.len(),
+                });
+            }
+            for (x, character) in row.into_iter().enumerate() {
+                let tile = terrain.index_of(character).ok_or(MapError::UnknownTile {
+                    row: y,
+                    column: x,
+                    character,
+                })?;
+                tiles.push(tile);
+            }
+        }
+        Ok(Self {
+            terrain: terrain.clone(),
+            tiles,
+            width: width as i32,
+            height: height as i32,
+        })
+    }
+    /// The grid the way maps store it, one string per row with a character per tile.
+    pub fn to_rows(&self) -> Vec<String> {
+        self.tiles
+            .chunks(self.width as usize)
+            .map(|row| {
+                row.iter()
+                    .map(|tile| self.terrain.terrains[*tile].character)
+                    .collect()
+            })
+            .collect()
+    }
+    pub fn width(&self) -> i32 {
+        self.width
+    }
+    pub fn height(&self) -> i32 {
+        self.height
+    }
+    pub fn in_bounds(&self, pos: Vector2<i32>) -> bool {
+        pos.x >= 0 && pos.y >= 0 && pos.x < self.width && pos.y < self.height
+    }
+    fn index_of(&self, pos: Vector2<i32>) -> Option<usize> {
+        if self.in_bounds(pos) {
+            Some((pos.y * self.width + pos.x) as usize)
+        } else {
+            None
+        }
+    }
+    fn pos_of(&self, index: usize) -> Vector2<i32> {
+        let index = index as i32;
+        [index % self.width, index / self.width].into()
+    }
+    pub fn get(&self, pos: Vector2<i32>) -> Option<&Terrain> {
+        self.index_of(pos)
+            .map(|index| &self.terrain.terrains[self.tiles[index]])
+    }
+    /// Whether no tile between `from` and `to` blocks line of sight. The two ends themselves don't count.
+    pub fn has_line_of_sight(&self, from: Vector2<i32>, to: Vector2<i32>) -> bool {
+        let (dx, dy) = ((to.x - from.x).abs(), -(to.y - from.y).abs());
+        let (step_x, step_y) = ((to.x - from.x).signum(), (to.y - from.y).signum());
+        let mut error = dx + dy;
+        let mut at = from;
+        while at != to {
+            let doubled = 2 * error;
+            if doubled >= dy {
+                error += dy;
+                at.x += step_x;
+            }
+            if doubled <= dx {
+                error += dx;
+                at.y += step_y;
+            }
+            if at != to
+                && self
+                    .get(at)
+                    .map(|tile| tile.blocks_line_of_sight)
+                    .unwrap_or(false)
+            {
+                return false;
+            }
+        }
+        true
+    }
+    /// The orthogonal neighbours of `pos` that are inside the grid.
+    pub fn neighbors(&self, pos: Vector2<i32>) -> impl Iterator<Item = Vector2<i32>> + '_ {
+        [(0, 1), (1, 0), (0, -1), (-1, 0)]
+            .iter()
+            .map(move |(x, y)| Vector2::from([pos.x + x, pos.y + y]))
+            .filter(move |next| self.in_bounds(*next))
+    }
+    pub fn iter(&self) -> impl Iterator<Item = (Vector2<i32>, &Terrain)> + '_ {
+        self.tiles
+            .iter()
+            .enumerate()
+            .map(move |(index, tile)| (self.pos_of(index), &self.terrain.terrains[*tile]))
+    }
+}
+
+#[derive(Deserialize)]
+pub struct ParseableCharacter {
+    pub x: i32,
+    pub y: i32,
+    pub char_type: String,
+    pub team: usize,
+    #[serde(default)]
+    pub commander: bool,
+}
+#[derive(Deserialize)]
+pub struct ParseableMap {
+    pub tiles: Vec<String>,
+    pub characters: Vec<ParseableCharacter>,
+    pub players: Vec<Player>,
+    #[serde(default = "default_win_conditions")]
+    pub win_conditions: Vec<WinCondition>,
+    /// For how many of their team's turns the dead stay on the field as corpses.
+    #[serde(default = "default_corpse_decay")]
+    pub corpse_decay: u32,
+}
+fn default_corpse_decay() -> u32 {
+    3
+}
+
+/// Checks what maps and saves share: that there are tiles and players, that every character
+/// stands on its own tile inside the map and that every team that is used has a player.
+/// `characters` holds the x, y and team of each character. Returns the width and height.
+pub fn check_layout(
+    tiles: &[String],
+    players: usize,
+    characters: impl IntoIterator<Item = (i32, i32, usize)>,
+    win_conditions: &[WinCondition],
+) -> Result<(usize, usize), MapError> {
+    let height = tiles.len();
+    let width = tiles.first().map(|row| row.chars().count()).unwrap_or(0);
+    if width == 0 {
+        return Err(MapError::EmptyMap);
+    }
+    if players == 0 {
+        return Err(MapError::NoPlayers);
+    }
+    let mut taken = HashSet::new();
+    for (x, y, team) in characters {
+        if x < 0 || y < 0 || x >= width as i32 || y >= height as i32 {
+            return Err(MapError::CharacterOutOfBounds { x, y });
+        }
+        if !taken.insert((x, y)) {
+            return Err(MapError::CharactersOnSameTile { x, y });
+        }
+        if team >= players {
+            return Err(MapError::UnknownTeam { team });
+        }
+    }
+    for condition in win_conditions {
+        if let Some(team) = condition.get_team().filter(|team| *team >= players) {
+            return Err(MapError::UnknownTeam { team });
+        }
+    }
+    Ok((width, height))
+}
+
+impl ParseableMap {
+    pub fn parse(
+        self,
+        data: &GameData,
+    ) -> Result<(Grid, CharacterContainer, TurnManager, Vec<WinCondition>), MapError> {
+        let (width, height) = check_layout(
+            &self.tiles,
+            self.players.len(),
+            self.characters
+                .iter()
+                .map(|character| (character.x, character.y, character.team)),
+            &self.win_conditions,
+        )?;
+        let grid = Grid::new(width, height, &self.tiles, &data.terrain)?;
+        let characters = CharacterContainer::new(&self, &data.character_types)?;
+        let turns = TurnManager::new(self.players);
+        Ok((grid, characters, turns, self.win_conditions))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::test_data::{game_data, map};
+
+    fn parse(map: ParseableMap) -> Option<MapError> {
+        map.parse(&game_data()).err()
+    }
+
+    #[test]
+    fn parses_a_valid_map() {
+        let map = map(
+            &["rfw", "rrr"],
+            &[(0, 0, "Soldier", 0), (2, 1, "Archer", 1)],
+        );
+        let (grid, characters, turns, _) = map.parse(&game_data()).unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid.to_rows(), vec!["rfw", "rrr"]);
+        assert_eq!(characters.characters.len(), 2);
+        assert_eq!(turns.players.len(), 2);
+    }
+
+    #[test]
+    fn rejects_broken_terrain() {
+        let duplicate = br#"[
+            {"character": "r", "name": "Road", "color": [0, 0, 0], "sprite": null, "move_costs": {}},
+            {"character": "R", "name": "Rock", "color": [0, 0, 0], "sprite": null, "move_costs": {}}
+        ]"#;
+        assert!(matches!(
+            TerrainTypes::from_json(duplicate).err(),
+            Some(MapError::DuplicateTerrain { character: 'R' })
+        ));
+        let free = br#"[
+            {"character": "i", "name": "Ice", "color": [0, 0, 0], "sprite": null, "move_costs": {"Ground": 0}}
+        ]"#;
+        assert!(matches!(
+            TerrainTypes::from_json(free).err(),
+            Some(MapError::FreeTerrain { .. })
+        ));
+    }
+
+    #[test]
+    fn rejects_broken_tiles() {
+        assert!(matches!(parse(map(&[], &[])), Some(MapError::EmptyMap)));
+        assert!(matches!(
+            parse(map(&["rrr", "rr"], &[])),
+            Some(MapError::RaggedRow {
+                row: 1,
+                expected: 3,
+                found: 2,
+            })
+        ));
+        assert!(matches!(
+            parse(map(&["rrr", "rxr"], &[])),
+            Some(MapError::UnknownTile {
+                row: 1,
+                column: 1,
+                character: 'x',
+            })
+        ));
+    }
+
+    #[test]
+    fn rejects_broken_characters() {
+        assert!(matches!(
+            parse(map(&["rr"], &[(2, 0, "Soldier", 0)])),
+            Some(MapError::CharacterOutOfBounds { x: 2, y: 0 })
+        ));
+        assert!(matches!(
+            parse(map(&["rr"], &[(1, 0, "Soldier", 0), (1, 0, "Archer", 1)])),
+            Some(MapError::CharactersOnSameTile { x: 1, y: 0 })
+        ));
+        assert!(matches!(
+            parse(map(&["rr"], &[(0, 0, "Soldier", 2)])),
+            Some(MapError::UnknownTeam { team: 2 })
+        ));
+        assert!(matches!(
+            parse(map(&["rr"], &[(0, 0, "Dragon", 0)])),
+            Some(MapError::UnknownCharacterType { .. })
+        ));
+    }
+
+    #[test]
+    fn rejects_players_that_do_not_exist() {
+        let mut no_players = map(&["rr"], &[]);
+        no_players.players.clear();
+        assert!(matches!(parse(no_players), Some(MapError::NoPlayers)));
+        let mut unknown_team = map(&["rr"], &[]);
+        unknown_team.win_conditions = vec![WinCondition::SurviveTurns { team: 7, turns: 0 }];
+        assert!(matches!(
+            parse(unknown_team),
+            Some(MapError::UnknownTeam { team: 7 })
+        ));
+    }
+}
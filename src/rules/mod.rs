@@ -0,0 +1,16 @@
+//! The rules of the game, without anything to do with drawing or loading files.
+pub mod ai;
+pub mod battle;
+pub mod character;
+pub mod combat;
+pub mod data;
+pub mod error;
+pub mod grid;
+pub mod pathfinding;
+pub mod random;
+pub mod save;
+pub mod spells;
+#[cfg(test)]
+mod test_data;
+pub mod turn;
+pub mod victory;
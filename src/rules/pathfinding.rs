@@ -0,0 +1,133 @@
+use super::grid::Grid;
+use mint::Vector2;
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+};
+
+fn distance(from: Vector2<i32>, to: Vector2<i32>) -> usize {
+    ((from.x - to.x).abs() + (from.y - to.y).abs()) as usize
+}
+
+/// A* search from start to goal. `cost_of` returns what it costs to enter a tile, or None if it can't be entered.
+/// The returned path starts with `start` and ends with `goal`.
+pub fn find_path(
+    grid: &Grid,
+    start: Vector2<i32>,
+    goal: Vector2<i32>,
+    cost_of: impl Fn(Vector2<i32>) -> Option<usize>,
+) -> Option<Vec<Vector2<i32>>> {
+    let mut came_from = HashMap::new();
+    let mut costs = HashMap::new();
+    costs.insert(start, 0);
+    let mut to_visit = BinaryHeap::new();
+    to_visit.push(Reverse((distance(start, goal), 0, start.x, start.y)));
+    while let Some(Reverse((_, cost, x, y))) = to_visit.pop() {
+        let current: Vector2<i32> = [x, y].into();
+        if current == goal {
+            let mut path = vec![goal];
+            let mut at = goal;
+            while let Some(previous) = came_from.get(&at) {
+                path.push(*previous);
+                at = *previous;
+            }
+            path.reverse();
+            return Some(path);
+        }
+        if costs
+            .get(&current)
+            .map(|known| *known < cost)
+            .unwrap_or(false)
+        {
+            continue;
+        }
+        for next in grid.neighbors(current) {
+            let next_cost = match cost_of(next) {
+                Some(tile_cost) => cost + tile_cost,
+                None => continue,
+            };
+            if costs
+                .get(&next)
+                .map(|known| *known <= next_cost)
+                .unwrap_or(false)
+            {
+                continue;
+            }
+            costs.insert(next, next_cost);
+            came_from.insert(next, current);
+            to_visit.push(Reverse((
+                next_cost + distance(next, goal),
+                next_cost,
+                next.x,
+                next.y,
+            )));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::{
+        character::MovementClass,
+        test_data::{at, grid},
+    };
+
+    fn find_ground_path(
+        rows: &[&str],
+        start: Vector2<i32>,
+        goal: Vector2<i32>,
+    ) -> Option<Vec<Vector2<i32>>> {
+        let grid = grid(rows);
+        find_path(&grid, start, goal, |pos| {
+            grid.get(pos)?.get_move_cost(MovementClass::Ground)
+        })
+    }
+
+    #[test]
+    fn path_to_the_start_is_just_the_start() {
+        assert_eq!(
+            find_ground_path(&["rr"], at(0, 0), at(0, 0)),
+            Some(vec![at(0, 0)])
+        );
+    }
+
+    #[test]
+    fn goes_around_expensive_terrain() {
+        let path = find_ground_path(&["rfffr", "rrrrr"], at(0, 0), at(4, 0));
+        assert_eq!(
+            path,
+            Some(vec![
+                at(0, 0),
+                at(0, 1),
+                at(1, 1),
+                at(2, 1),
+                at(3, 1),
+                at(4, 1),
+                at(4, 0),
+            ])
+        );
+    }
+
+    #[test]
+    fn goes_through_terrain_when_that_is_cheaper() {
+        let path = find_ground_path(&["rfr", "rrr"], at(0, 0), at(2, 0));
+        assert_eq!(path, Some(vec![at(0, 0), at(1, 0), at(2, 0)]));
+    }
+
+    #[test]
+    fn goes_around_blocked_tiles() {
+        let path = find_ground_path(&["rwr", "rrr"], at(0, 0), at(2, 0));
+        assert_eq!(
+            path,
+            Some(vec![at(0, 0), at(0, 1), at(1, 1), at(2, 1), at(2, 0)])
+        );
+    }
+
+    #[test]
+    fn fails_when_the_goal_can_not_be_reached() {
+        assert_eq!(find_ground_path(&["rwr"], at(0, 0), at(2, 0)), None);
+        assert_eq!(find_ground_path(&["rw"], at(0, 0), at(1, 0)), None);
+    }
+}
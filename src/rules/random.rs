@@ -0,0 +1,25 @@
+/// A small seedable random number generator (splitmix64), so fights don't need an extra dependency.
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+    /// Passing this to `new` gives a generator that continues where this one is.
+    pub fn get_state(&self) -> u64 {
+        self.state
+    }
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+    /// Returns true `percent` out of 100 times.
+    pub fn chance(&mut self, percent: i32) -> bool {
+        ((self.next_u64() % 100) as i32) < percent
+    }
+}
@@ -0,0 +1,299 @@
+use super::{
+    battle::Battle,
+    character::{Buff, Character, CharacterContainer, Corpse},
+    data::GameData,
+    error::MapError,
+    grid::{check_layout, Grid},
+    random::Rng,
+    turn::{Player, TurnManager},
+    victory::WinCondition,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Serialize, Deserialize)]
+pub struct SavedCharacter {
+    pub id: u64,
+    pub x: i32,
+    pub y: i32,
+    pub char_type: String,
+    pub team: usize,
+    pub commander: bool,
+    pub hp: i32,
+    pub mana: i32,
+    pub has_moved: bool,
+    pub buffs: Vec<Buff>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SavedCorpse {
+    pub x: i32,
+    pub y: i32,
+    pub team: usize,
+    pub turns_left: u32,
+}
+
+/// Everything needed to continue a battle later.
+/// Character types are stored by name, so they come from the current `units.json` when loading.
+#[derive(Serialize, Deserialize)]
+pub struct SaveGame {
+    pub map_file: String,
+    pub tiles: Vec<String>,
+    pub characters: Vec<SavedCharacter>,
+    pub corpses: Vec<SavedCorpse>,
+    pub corpse_decay: u32,
+    pub next_id: u64,
+    pub players: Vec<Player>,
+    pub active_player: usize,
+    pub turn: u32,
+    pub win_conditions: Vec<WinCondition>,
+    pub rng_state: u64,
+}
+
+impl SaveGame {
+    pub fn new(battle: &Battle, map_file: &str) -> Self {
+        let mut characters = battle
+            .characters
+            .characters
+            .values()
+            .map(|character| SavedCharacter {
+                id: character.id,
+                x: character.position.x,
+                y: character.position.y,
+                char_type: character.char_type.name.clone(),
+                team: character.team,
+                commander: character.commander,
+                hp: character.hp,
+                mana: character.mana,
+                has_moved: character.has_moved,
+                buffs: character.buffs.clone(),
+            })
+            .collect::<Vec<_>>();
+        characters.sort_by_key(|character| character.id);
+        Self {
+            map_file: map_file.to_owned(),
+            tiles: battle.grid.to_rows(),
+            characters,
+            corpses: battle
+                .characters
+                .corpses
+                .iter()
+                .map(|(position, corpse)| SavedCorpse {
+                    x: position.x,
+                    y: position.y,
+                    team: corpse.team,
+                    turns_left: corpse.turns_left,
+                })
+                .collect(),
+            corpse_decay: battle.characters.get_corpse_decay(),
+            next_id: battle.characters.next_id,
+            players: battle.turns.players.clone(),
+            active_player: battle.turns.active_player,
+            turn: battle.turns.turn,
+            win_conditions: battle.win_conditions.clone(),
+            rng_state: battle.rng.get_state(),
+        }
+    }
+
+    /// Rebuilds the battle. Fails if the save uses terrain or character types that no longer exist,
+    /// if it doesn't pass the same checks as a map or if its ids, hp or corpses can't be right.
+    pub fn restore(self, data: &GameData) -> Result<Battle, MapError> {
+        let (width, height) = check_layout(
+            &self.tiles,
+            self.players.len(),
+            self.characters
+                .iter()
+                .map(|character| (character.x, character.y, character.team)),
+            &self.win_conditions,
+        )?;
+        if self.active_player >= self.players.len() {
+            return Err(MapError::UnknownTeam {
+                team: self.active_player,
+            });
+        }
+        let grid = Grid::new(width, height, &self.tiles, &data.terrain)?;
+        let mut characters = HashMap::new();
+        for saved in self.characters {
+            let char_type = data
+                .character_types
+                .get(&saved.char_type)
+                .cloned()
+                .ok_or_else(|| MapError::UnknownCharacterType {
+                    name: saved.char_type.clone(),
+                })?;
+            if saved.hp <= 0 || saved.hp > char_type.get_max_hp() {
+                return Err(MapError::InvalidHp {
+                    id: saved.id,
+                    hp: saved.hp,
+                });
+            }
+            if saved.id >= self.next_id {
+                return Err(MapError::NextIdInUse {
+                    next_id: self.next_id,
+                });
+            }
+            let mut character = Character::new(
+                saved.id,
+                [saved.x, saved.y].into(),
+                char_type,
+                saved.team,
+                saved.commander,
+            );
+            character.hp = saved.hp;
+            character.mana = saved.mana;
+            character.has_moved = saved.has_moved;
+            character.buffs = saved.buffs;
+            if characters.insert(saved.id, character).is_some() {
+                return Err(MapError::DuplicateCharacterId { id: saved.id });
+            }
+        }
+        let mut corpses = HashMap::new();
+        for corpse in self.corpses {
+            let position = [corpse.x, corpse.y].into();
+            if !grid.in_bounds(position) {
+                return Err(MapError::CorpseOutOfBounds {
+                    x: corpse.x,
+                    y: corpse.y,
+                });
+            }
+            corpses.insert(
+                position,
+                Corpse {
+                    team: corpse.team,
+                    turns_left: corpse.turns_left,
+                },
+            );
+        }
+        let mut turns = TurnManager::new(self.players);
+        turns.active_player = self.active_player;
+        turns.turn = self.turn;
+        Ok(Battle {
+            grid,
+            characters: CharacterContainer::restore(
+                characters,
+                corpses,
+                self.next_id,
+                self.corpse_decay,
+            ),
+            turns,
+            win_conditions: self.win_conditions,
+            rng: Rng::new(self.rng_state),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::test_data::{at, battle, finish_walking, game_data, map};
+
+    fn round_trip(save: &SaveGame) -> SaveGame {
+        serde_json::from_str(&serde_json::to_string(save).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn a_saved_battle_continues_where_it_was() {
+        let mut battle = battle(map(
+            &["rrrr", "rfrw"],
+            &[
+                (0, 0, "Soldier", 0),
+                (3, 0, "Soldier", 1),
+                (0, 1, "Archer", 0),
+            ],
+        ));
+        battle
+            .move_character(0, vec![at(0, 0), at(1, 0), at(2, 0)])
+            .unwrap();
+        finish_walking(&mut battle);
+        battle.attack(0, 1).unwrap();
+        battle.end_turn().unwrap();
+        battle.characters.corpses.insert(
+            at(1, 1),
+            Corpse {
+                team: 1,
+                turns_left: 2,
+            },
+        );
+
+        let save = round_trip(&SaveGame::new(&battle, "map.json"));
+        assert_eq!(save.map_file, "map.json");
+        let restored = save.restore(&game_data()).unwrap();
+
+        assert_eq!(restored.grid.to_rows(), battle.grid.to_rows());
+        assert_eq!(restored.turns.active_player, 1);
+        assert_eq!(restored.turns.turn, 1);
+        assert_eq!(restored.rng.get_state(), battle.rng.get_state());
+        assert_eq!(restored.characters.next_id, battle.characters.next_id);
+        assert_eq!(restored.characters.characters.len(), 3);
+        for (id, character) in &battle.characters.characters {
+            let loaded = &restored.characters.characters[id];
+            assert_eq!(loaded.position, character.position);
+            assert_eq!(loaded.char_type.name, character.char_type.name);
+            assert_eq!(loaded.team, character.team);
+            assert_eq!(loaded.hp, character.hp);
+            assert_eq!(loaded.has_moved, character.has_moved);
+        }
+        let corpse = &restored.characters.corpses[&at(1, 1)];
+        assert_eq!((corpse.team, corpse.turns_left), (1, 2));
+    }
+
+    #[test]
+    fn restore_checks_the_save_like_a_map() {
+        let battle = battle(map(&["rrr"], &[(0, 0, "Soldier", 0), (2, 0, "Soldier", 1)]));
+        let mut out_of_bounds = SaveGame::new(&battle, "map.json");
+        out_of_bounds.characters[0].x = 5;
+        assert!(matches!(
+            out_of_bounds.restore(&game_data()).err(),
+            Some(MapError::CharacterOutOfBounds { x: 5, y: 0 })
+        ));
+        let mut same_tile = SaveGame::new(&battle, "map.json");
+        same_tile.characters[0].x = 2;
+        assert!(matches!(
+            same_tile.restore(&game_data()).err(),
+            Some(MapError::CharactersOnSameTile { x: 2, y: 0 })
+        ));
+        let mut unknown_team = SaveGame::new(&battle, "map.json");
+        unknown_team.characters[1].team = 3;
+        assert!(matches!(
+            unknown_team.restore(&game_data()).err(),
+            Some(MapError::UnknownTeam { team: 3 })
+        ));
+        let mut unknown_player = SaveGame::new(&battle, "map.json");
+        unknown_player.active_player = 2;
+        assert!(matches!(
+            unknown_player.restore(&game_data()).err(),
+            Some(MapError::UnknownTeam { team: 2 })
+        ));
+        let mut duplicate_id = SaveGame::new(&battle, "map.json");
+        duplicate_id.characters[1].id = 0;
+        assert!(matches!(
+            duplicate_id.restore(&game_data()).err(),
+            Some(MapError::DuplicateCharacterId { id: 0 })
+        ));
+        let mut stale_next_id = SaveGame::new(&battle, "map.json");
+        stale_next_id.next_id = 1;
+        assert!(matches!(
+            stale_next_id.restore(&game_data()).err(),
+            Some(MapError::NextIdInUse { next_id: 1 })
+        ));
+        for hp in vec![0, 11] {
+            let mut invalid_hp = SaveGame::new(&battle, "map.json");
+            invalid_hp.characters[1].hp = hp;
+            assert!(matches!(
+                invalid_hp.restore(&game_data()).err(),
+                Some(MapError::InvalidHp { id: 1, hp: found }) if found == hp
+            ));
+        }
+        let mut corpse_out_of_bounds = SaveGame::new(&battle, "map.json");
+        corpse_out_of_bounds.corpses.push(SavedCorpse {
+            x: 1,
+            y: -1,
+            team: 0,
+            turns_left: 1,
+        });
+        assert!(matches!(
+            corpse_out_of_bounds.restore(&game_data()).err(),
+            Some(MapError::CorpseOutOfBounds { x: 1, y: -1 })
+        ));
+    }
+}
@@ -0,0 +1,202 @@
+use super::{
+    character::{within_range, Buff, Character, CharacterContainer, CharacterTypes},
+    error::MapError,
+    grid::Grid,
+};
+use mint::Vector2;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+#[derive(Deserialize, Clone)]
+#[serde(tag = "shape")]
+pub enum Area {
+    Single,
+    /// Every tile within `radius` steps.
+    Diamond {
+        radius: i32,
+    },
+    /// Every tile within `radius` tiles, diagonals included.
+    Square {
+        radius: i32,
+    },
+}
+
+impl Area {
+    pub fn get_tiles(&self, center: Vector2<i32>) -> Vec<Vector2<i32>> {
+        let radius = match self {
+            Area::Single => 0,
+            Area::Diamond { radius } | Area::Square { radius } => *radius,
+        };
+        let mut tiles = Vec::new();
+        for x in -radius..=radius {
+            for y in -radius..=radius {
+                if let Area::Diamond { .. } = self {
+                    if x.abs() + y.abs() > radius {
+                        continue;
+                    }
+                }
+                tiles.push([center.x + x, center.y + y].into());
+            }
+        }
+        tiles
+    }
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(tag = "type")]
+pub enum SpellEffect {
+    /// Hurts every enemy in the area.
+    Damage { amount: i32 },
+    /// Heals every ally in the area, up to their max hp.
+    Heal { amount: i32 },
+    /// Makes every ally in the area stronger for a few turns.
+    Buff {
+        attack: i32,
+        defense: i32,
+        turns: u32,
+    },
+    /// Moves the caster to the targeted tile.
+    Teleport,
+    /// Raises the targeted corpse as a unit of the given type on the caster's team.
+    Raise { into: String },
+}
+
+#[derive(Deserialize, Clone)]
+pub struct Spell {
+    pub name: String,
+    pub range: i32,
+    pub area: Area,
+    pub cost: i32,
+    pub effect: SpellEffect,
+}
+
+/// Every spell defined in `spells.json`, by name.
+#[derive(Default)]
+pub struct Spells {
+    spells: HashMap<String, Spell>,
+}
+
+impl Spells {
+    /// Reads the spells from the contents of `spells.json`.
+    pub fn from_json(file: &[u8]) -> Result<Self, MapError> {
+        let spells = serde_json::from_slice::<Vec<Spell>>(file)?
+            .into_iter()
+            .map(|spell| (spell.name.clone(), spell))
+            .collect();
+        Ok(Self { spells })
+    }
+    pub fn get(&self, name: &str) -> Option<&Spell> {
+        self.spells.get(name)
+    }
+    /// The spells from the character's spellbook it has enough mana for.
+    pub fn castable_by<'a>(&'a self, character: &Character) -> Vec<&'a Spell> {
+        character
+            .char_type
+            .spells
+            .iter()
+            .filter_map(|name| self.get(name))
+            .filter(|spell| spell.cost <= character.mana)
+            .collect()
+    }
+}
+
+pub struct CastResult {
+    pub affected: Vec<u64>,
+    pub dead: Vec<Character>,
+}
+
+impl Spell {
+    /// Every tile the caster could aim this spell at.
+    pub fn get_targets(
+        &self,
+        caster: u64,
+        characters: &CharacterContainer,
+        grid: &Grid,
+    ) -> Vec<Vector2<i32>> {
+        let position = match characters.characters.get(&caster) {
+            Some(character) => character.position,
+            None => return Vec::new(),
+        };
+        grid.iter()
+            .map(|(pos, _)| pos)
+            .filter(|pos| {
+                within_range(position, *pos, self.range) && grid.has_line_of_sight(position, *pos)
+            })
+            .filter(|pos| match self.effect {
+                SpellEffect::Teleport => characters.can_stand_on(caster, *pos, grid),
+                SpellEffect::Raise { .. } => {
+                    characters.corpses.contains_key(pos)
+                        && characters.get_char_id_by_pos(*pos).is_none()
+                }
+                _ => true,
+            })
+            .collect()
+    }
+
+    /// Spends the caster's mana and applies the effect of the spell around `target`.
+    /// Characters that died are removed from the container and returned as part of the result.
+    pub fn cast(
+        &self,
+        caster: u64,
+        target: Vector2<i32>,
+        characters: &mut CharacterContainer,
+        grid: &Grid,
+        types: &CharacterTypes,
+    ) -> Option<CastResult> {
+        if !self.get_targets(caster, characters, grid).contains(&target) {
+            return None;
+        }
+        let raised_type = match &self.effect {
+            SpellEffect::Raise { into } => Some(types.get(into)?.clone()),
+            _ => None,
+        };
+        let caster_character = characters.characters.get_mut(&caster)?;
+        if caster_character.mana < self.cost {
+            return None;
+        }
+        caster_character.mana -= self.cost;
+        let team = caster_character.team;
+        if let SpellEffect::Teleport = self.effect {
+            caster_character.position = target;
+            return Some(CastResult {
+                affected: vec![caster],
+                dead: Vec::new(),
+            });
+        }
+        if let Some(raised_type) = raised_type {
+            let raised = characters.raise_corpse(target, raised_type, team);
+            return Some(CastResult {
+                affected: raised.into_iter().collect(),
+                dead: Vec::new(),
+            });
+        }
+        let area = self.area.get_tiles(target);
+        let mut affected = Vec::new();
+        for character in characters.characters.values_mut() {
+            if !area.contains(&character.position) {
+                continue;
+            }
+            match &self.effect {
+                SpellEffect::Damage { amount } if character.team != team => character.hp -= amount,
+                SpellEffect::Heal { amount } if character.team == team => {
+                    character.hp = (character.hp + amount).min(character.char_type.get_max_hp())
+                }
+                SpellEffect::Buff {
+                    attack,
+                    defense,
+                    turns,
+                } if character.team == team => character.buffs.push(Buff {
+                    attack: *attack,
+                    defense: *defense,
+                    turns_left: *turns,
+                }),
+                _ => continue,
+            }
+            affected.push(character.id);
+        }
+        Some(CastResult {
+            affected,
+            dead: characters.remove_dead(),
+        })
+    }
+}
@@ -0,0 +1,121 @@
+//! Terrain, units and maps for the tests, so they don't change when the files in `static` do.
+use super::{
+    battle::Battle,
+    character::CharacterTypes,
+    data::GameData,
+    grid::{Grid, ParseableCharacter, ParseableMap, TerrainTypes},
+    spells::Spells,
+    turn::Player,
+    victory::default_win_conditions,
+};
+use mint::Vector2;
+
+/// Roads are cheap and can't be missed on, forests are slow and give cover, water can't be entered.
+const TERRAIN: &str = r#"[
+    {
+        "character": "r",
+        "name": "Road",
+        "color": [0, 0, 0],
+        "sprite": null,
+        "move_costs": { "Ground": 1 },
+        "evasion": -10
+    },
+    {
+        "character": "f",
+        "name": "Forest",
+        "color": [0, 100, 0],
+        "sprite": null,
+        "move_costs": { "Ground": 2 },
+        "defense_bonus": 1,
+        "evasion": 20,
+        "blocks_line_of_sight": true
+    },
+    {
+        "character": "w",
+        "name": "Water",
+        "color": [0, 0, 255],
+        "sprite": null,
+        "move_costs": {}
+    }
+]"#;
+
+const UNITS: &str = r#"[
+    {
+        "name": "Soldier",
+        "walk_range": 3,
+        "attack_range": 2,
+        "hp": 10,
+        "attack": 5,
+        "defense": 2,
+        "movement_class": "Ground",
+        "sprite": null
+    },
+    {
+        "name": "Archer",
+        "walk_range": 2,
+        "attack_range": 4,
+        "hp": 6,
+        "attack": 4,
+        "defense": 1,
+        "movement_class": "Ground",
+        "sprite": null
+    }
+]"#;
+
+pub fn at(x: i32, y: i32) -> Vector2<i32> {
+    [x, y].into()
+}
+
+pub fn game_data() -> GameData {
+    GameData {
+        character_types: CharacterTypes::from_json(UNITS.as_bytes()).unwrap(),
+        terrain: TerrainTypes::from_json(TERRAIN.as_bytes()).unwrap(),
+        spells: Spells::default(),
+    }
+}
+
+pub fn grid(rows: &[&str]) -> Grid {
+    let rows = rows.iter().map(|row| row.to_string()).collect::<Vec<_>>();
+    Grid::new(rows[0].len(), rows.len(), &rows, &game_data().terrain).unwrap()
+}
+
+/// A map for two players where the last team standing wins.
+/// Characters are given as x, y, type and team and get their index as id.
+pub fn map(rows: &[&str], characters: &[(i32, i32, &str, usize)]) -> ParseableMap {
+    ParseableMap {
+        tiles: rows.iter().map(|row| row.to_string()).collect(),
+        characters: characters
+            .iter()
+            .map(|(x, y, char_type, team)| ParseableCharacter {
+                x: *x,
+                y: *y,
+                char_type: char_type.to_string(),
+                team: *team,
+                commander: false,
+            })
+            .collect(),
+        players: vec![
+            Player {
+                name: "Red".into(),
+                ai: None,
+            },
+            Player {
+                name: "Blue".into(),
+                ai: None,
+            },
+        ],
+        win_conditions: default_win_conditions(),
+        corpse_decay: 3,
+    }
+}
+
+pub fn battle(map: ParseableMap) -> Battle {
+    Battle::new(map, &game_data(), 0).unwrap()
+}
+
+/// Plays the walking animation to the end, as nothing can be done while it plays.
+pub fn finish_walking(battle: &mut Battle) {
+    while battle.characters.is_moving() {
+        battle.characters.update();
+    }
+}
@@ -0,0 +1,40 @@
+use super::{ai::AiConfig, character::CharacterContainer};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Player {
+    pub name: String,
+    /// How the computer plays this side, None if a person plays it.
+    #[serde(default)]
+    pub ai: Option<AiConfig>,
+}
+
+pub struct TurnManager {
+    pub players: Vec<Player>,
+    pub active_player: usize,
+    pub turn: u32,
+}
+
+impl TurnManager {
+    pub fn new(players: Vec<Player>) -> Self {
+        Self {
+            players,
+            active_player: 0,
+            turn: 1,
+        }
+    }
+    pub fn is_active(&self, team: usize) -> bool {
+        self.active_player == team
+    }
+    pub fn get_active_player(&self) -> &Player {
+        &self.players[self.active_player]
+    }
+    /// Hands the turn to the next player and lets all of their characters act again.
+    pub fn end_turn(&mut self, characters: &mut CharacterContainer) {
+        self.active_player = (self.active_player + 1) % self.players.len();
+        if self.active_player == 0 {
+            self.turn += 1;
+        }
+        characters.start_turn(self.active_player);
+    }
+}
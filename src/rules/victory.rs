@@ -0,0 +1,192 @@
+use super::{character::CharacterContainer, turn::TurnManager};
+use mint::Vector2;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(tag = "type")]
+pub enum WinCondition {
+    /// The last team that still has characters wins.
+    EliminateAll,
+    /// `team` wins once it made it through the given amount of turns.
+    SurviveTurns { team: usize, turns: u32 },
+    /// `team` wins as soon as one of its characters stands on the given tile.
+    ReachTile { team: usize, x: i32, y: i32 },
+    /// A team whose commanders are all dead is out, the last team standing wins.
+    KillCommander,
+}
+
+pub fn default_win_conditions() -> Vec<WinCondition> {
+    vec![WinCondition::EliminateAll]
+}
+
+fn last_team_standing(
+    turns: &TurnManager,
+    mut is_standing: impl FnMut(usize) -> bool,
+) -> Option<usize> {
+    let mut standing = (0..turns.players.len()).filter(|team| is_standing(*team));
+    match (standing.next(), standing.next()) {
+        (Some(team), None) => Some(team),
+        _ => None,
+    }
+}
+
+impl WinCondition {
+    /// The team this condition is about, if it is about a single one.
+    pub fn get_team(&self) -> Option<usize> {
+        match self {
+            WinCondition::SurviveTurns { team, .. } | WinCondition::ReachTile { team, .. } => {
+                Some(*team)
+            }
+            WinCondition::EliminateAll | WinCondition::KillCommander => None,
+        }
+    }
+    /// Returns the team that won because of this condition, if any.
+    pub fn check(&self, characters: &CharacterContainer, turns: &TurnManager) -> Option<usize> {
+        match self {
+            WinCondition::EliminateAll => last_team_standing(turns, |team| {
+                characters
+                    .characters
+                    .values()
+                    .any(|character| character.team == team)
+            }),
+            WinCondition::SurviveTurns {
+                team,
+                turns: needed,
+            } => {
+                if turns.turn > *needed {
+                    Some(*team)
+                } else {
+                    None
+                }
+            }
+            WinCondition::ReachTile { team, x, y } => characters
+                .characters
+                .values()
+                .find(|character| {
+                    character.team == *team
+                        && character.position.x == *x
+                        && character.position.y == *y
+                })
+                .map(|character| character.team),
+            WinCondition::KillCommander => last_team_standing(turns, |team| {
+                characters
+                    .characters
+                    .values()
+                    .any(|character| character.team == team && character.commander)
+            }),
+        }
+    }
+}
+
+pub fn check_victory(
+    conditions: &[WinCondition],
+    characters: &CharacterContainer,
+    turns: &TurnManager,
+) -> Option<usize> {
+    conditions
+        .iter()
+        .find_map(|condition| condition.check(characters, turns))
+}
+
+/// The tiles `team` has to get to to win: tiles it has to reach and enemy commanders it has to kill.
+pub fn objectives(
+    conditions: &[WinCondition],
+    characters: &CharacterContainer,
+    team: usize,
+) -> Vec<Vector2<i32>> {
+    let mut objectives = Vec::new();
+    for condition in conditions {
+        match condition {
+            WinCondition::ReachTile { team: needed, x, y } if *needed == team => {
+                objectives.push([*x, *y].into())
+            }
+            WinCondition::KillCommander => objectives.extend(
+                characters
+                    .characters
+                    .values()
+                    .filter(|character| character.team != team && character.commander)
+                    .map(|character| character.position),
+            ),
+            _ => {}
+        }
+    }
+    objectives
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::{
+        battle::Battle,
+        test_data::{at, battle, map},
+    };
+
+    fn with_conditions(conditions: Vec<WinCondition>) -> Battle {
+        let mut map = map(
+            &["rrr"],
+            &[
+                (0, 0, "Soldier", 0),
+                (2, 0, "Soldier", 1),
+                (1, 0, "Archer", 1),
+            ],
+        );
+        map.characters[0].commander = true;
+        map.characters[1].commander = true;
+        map.win_conditions = conditions;
+        battle(map)
+    }
+
+    #[test]
+    fn eliminate_all_waits_for_the_last_team_standing() {
+        let mut battle = with_conditions(vec![WinCondition::EliminateAll]);
+        assert_eq!(battle.winner(), None);
+        battle.characters.characters.remove(&1);
+        assert_eq!(battle.winner(), None);
+        battle.characters.characters.remove(&2);
+        assert_eq!(battle.winner(), Some(0));
+    }
+
+    #[test]
+    fn kill_commander_only_counts_commanders() {
+        let mut battle = with_conditions(vec![WinCondition::KillCommander]);
+        assert_eq!(battle.winner(), None);
+        battle.characters.characters.remove(&2);
+        assert_eq!(battle.winner(), None);
+        battle.characters.characters.remove(&1);
+        assert_eq!(battle.winner(), Some(0));
+    }
+
+    #[test]
+    fn survive_turns_is_won_after_the_last_turn() {
+        let mut battle = with_conditions(vec![WinCondition::SurviveTurns { team: 1, turns: 2 }]);
+        battle.turns.turn = 2;
+        assert_eq!(battle.winner(), None);
+        battle.turns.turn = 3;
+        assert_eq!(battle.winner(), Some(1));
+    }
+
+    #[test]
+    fn reach_tile_needs_a_character_of_the_team_on_it() {
+        let mut battle = with_conditions(vec![WinCondition::ReachTile {
+            team: 1,
+            x: 0,
+            y: 0,
+        }]);
+        assert_eq!(battle.winner(), None);
+        battle.characters.characters.remove(&0);
+        battle.characters.characters.get_mut(&2).unwrap().position = at(0, 0);
+        assert_eq!(battle.winner(), Some(1));
+    }
+
+    #[test]
+    fn the_first_condition_that_is_met_wins() {
+        let mut battle = with_conditions(vec![
+            WinCondition::SurviveTurns { team: 1, turns: 1 },
+            WinCondition::EliminateAll,
+        ]);
+        battle.characters.characters.remove(&1);
+        battle.characters.characters.remove(&2);
+        battle.turns.turn = 2;
+        assert_eq!(battle.winner(), Some(1));
+    }
+}
@@ -0,0 +1,77 @@
+use crate::input::Keymap;
+use magic_wars::rules::save::SaveGame;
+use quicksilver::saving::{load, save, Location, SaveError};
+
+const APP_NAME: &str = "dead_wars";
+/// The slot the pause menu saves to and loads from.
+pub const MANUAL_SAVE: &str = "battle";
+const KEYMAP: &str = "keymap";
+
+/// Stores the save in local storage on the web, or in the data directory on desktop.
+pub fn write_save(name: &str, save_game: &SaveGame) -> Result<(), SaveError> {
+    save(Location::Data, APP_NAME, name, save_game)
+}
+
+pub fn read_save(name: &str) -> Result<SaveGame, SaveError> {
+    load(Location::Data, APP_NAME, name)
+}
+
+/// How many autosaves are kept before the oldest one gets overwritten.
+const AUTOSAVES_KEPT: usize = 5;
+/// Lists the slots that hold autosaves, newest first.
+const AUTOSAVE_INDEX: &str = "autosaves";
+
+fn autosave_slot(slot: usize) -> String {
+    format!("autosave_{}", slot)
+}
+
+fn read_autosave_index() -> Vec<usize> {
+    load(Location::Data, APP_NAME, AUTOSAVE_INDEX).unwrap_or_default()
+}
+
+/// Saves into the slot of the oldest autosave once all slots are in use.
+pub fn write_autosave(save_game: &SaveGame) -> Result<(), SaveError> {
+    let mut index = read_autosave_index();
+    let slot = if index.len() < AUTOSAVES_KEPT {
+        (0..AUTOSAVES_KEPT)
+            .find(|slot| !index.contains(slot))
+            .unwrap_or(0)
+    } else {
+        index[index.len() - 1]
+    };
+    write_save(&autosave_slot(slot), save_game)?;
+    index.retain(|existing| *existing != slot);
+    index.insert(0, slot);
+    index.truncate(AUTOSAVES_KEPT);
+    save(Location::Data, APP_NAME, AUTOSAVE_INDEX, &index)
+}
+
+pub fn read_latest_autosave() -> Option<SaveGame> {
+    let slot = *read_autosave_index().first()?;
+    read_save(&autosave_slot(slot)).ok()
+}
+
+/// Forgets every autosave, so a finished battle isn't offered to continue.
+pub fn clear_autosaves() -> Result<(), SaveError> {
+    save(
+        Location::Data,
+        APP_NAME,
+        AUTOSAVE_INDEX,
+        &Vec::<usize>::new(),
+    )
+}
+
+pub fn write_keymap(keymap: &Keymap) -> Result<(), SaveError> {
+    save(Location::Data, APP_NAME, KEYMAP, keymap)
+}
+
+/// The keymap saved by the controls screen, or the default one if nothing got changed yet.
+pub fn read_keymap() -> Keymap {
+    match load::<Keymap>(Location::Data, APP_NAME, KEYMAP) {
+        Ok(mut keymap) => {
+            keymap.fill_missing();
+            keymap
+        }
+        Err(_) => Keymap::default(),
+    }
+}
@@ -0,0 +1,102 @@
+use super::{
+    game::Game,
+    screen::{Screen, Transition},
+};
+use crate::{
+    panel::{Panel, PanelConfig},
+    Wrapper,
+};
+use async_trait::async_trait;
+use magic_wars::rules::ai::AiConfig;
+use mergui::{FontStyle, MFont};
+use quicksilver::{geom::Vector, graphics::Color, lifecycle::Event, Result};
+
+pub enum BattleEndOptions {
+    PlayAgain,
+    MainMenu,
+}
+
+pub struct BattleEnd {
+    pub winner: String,
+    pub turn: u32,
+    pub map_file: String,
+    pub controllers: Vec<Option<AiConfig>>,
+    font: Option<MFont>,
+    panel: Option<Panel<BattleEndOptions>>,
+}
+
+impl BattleEnd {
+    pub fn new(
+        winner: String,
+        turn: u32,
+        map_file: String,
+        controllers: Vec<Option<AiConfig>>,
+    ) -> Self {
+        Self {
+            winner,
+            turn,
+            map_file,
+            controllers,
+            font: None,
+            panel: None,
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl Screen for BattleEnd {
+    async fn draw(&mut self, wrapper: &mut Wrapper<'_>) -> Result<()> {
+        if let Some(font) = &self.font {
+            let style = FontStyle {
+                font: font.clone(),
+                location: wrapper.get_pos_vector(0.1, 0.3),
+                color: Color::WHITE,
+            };
+            style.draw(
+                &mut wrapper.gfx,
+                &format!("{} won after {} turns!", self.winner, self.turn),
+            )?;
+        }
+        if let Some(panel) = &self.panel {
+            panel.draw(Vector::new(0, 0), wrapper)?;
+        }
+        Ok(())
+    }
+    async fn update(&mut self, wrapper: &mut Wrapper<'_>) -> Result<Option<Transition>> {
+        if let None = self.font {
+            self.font = Some(wrapper.get_font(40.).await?);
+        }
+        if let None = self.panel {
+            self.panel = Some(Panel::new(PanelConfig {
+                options: vec![
+                    ("Play again".into(), BattleEndOptions::PlayAgain),
+                    ("Main menu".into(), BattleEndOptions::MainMenu),
+                ],
+                font: wrapper.get_font(20.).await?,
+                top_left: wrapper.get_pos_vector(0.1, 0.5),
+                width: 150.,
+                background: wrapper.get_image("pixel.png".into()).await?,
+                text_size: 30.,
+            }));
+        }
+        Ok(None)
+    }
+    async fn event(
+        &mut self,
+        wrapper: &mut Wrapper<'_>,
+        event: &Event,
+    ) -> Result<Option<Transition>> {
+        if let Some(panel) = &mut self.panel {
+            match panel.event(wrapper, event) {
+                Some(BattleEndOptions::PlayAgain) => {
+                    return Ok(Some(
+                        Game::start(&self.map_file, &self.controllers, wrapper).await,
+                    ))
+                }
+                Some(BattleEndOptions::MainMenu) => return Ok(Some(Transition::Pop)),
+                None => {}
+            }
+        }
+        Ok(None)
+    }
+}
@@ -0,0 +1,137 @@
+use super::{
+    game::Game,
+    map_picker::MapPicker,
+    screen::{Screen, Transition},
+};
+use crate::{
+    panel::{Panel, PanelConfig},
+    Wrapper,
+};
+use async_trait::async_trait;
+use magic_wars::rules::{
+    ai::{AiConfig, Difficulty, Personality},
+    turn::Player,
+};
+use quicksilver::{lifecycle::Event, Result};
+
+pub enum BattleSetupOptions {
+    Controller(usize),
+    Personality(usize),
+    Start,
+    Back,
+}
+
+/// Goes from a person playing to ever harder AI and back.
+fn next_controller(controller: Option<AiConfig>) -> Option<AiConfig> {
+    match controller {
+        None => Some(AiConfig {
+            difficulty: Difficulty::Random,
+            personality: Personality::default(),
+        }),
+        Some(AiConfig {
+            difficulty: Difficulty::Lookahead,
+            ..
+        }) => None,
+        Some(config) => Some(AiConfig {
+            difficulty: config.difficulty.next(),
+            ..config
+        }),
+    }
+}
+
+/// Lets the player pick who controls each team before a battle starts.
+pub struct BattleSetup {
+    map_file: String,
+    players: Vec<Player>,
+    panel: Option<Panel<BattleSetupOptions>>,
+}
+
+impl BattleSetup {
+    pub fn new(map_file: String, players: Vec<Player>) -> Self {
+        Self {
+            map_file,
+            players,
+            panel: None,
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl Screen for BattleSetup {
+    async fn draw(&mut self, wrapper: &mut Wrapper<'_>) -> Result<()> {
+        if let Some(panel) = &self.panel {
+            panel.draw([0., 0.].into(), wrapper)?;
+        }
+        Ok(())
+    }
+    async fn update(&mut self, wrapper: &mut Wrapper<'_>) -> Result<Option<Transition>> {
+        if let None = self.panel {
+            let mut options = Vec::new();
+            for (team, player) in self.players.iter().enumerate() {
+                match player.ai {
+                    Some(config) => {
+                        options.push((
+                            format!("{}: AI ({})", player.name, config.difficulty.get_name()),
+                            BattleSetupOptions::Controller(team),
+                        ));
+                        options.push((
+                            format!("{} plays {}", player.name, config.personality.get_name()),
+                            BattleSetupOptions::Personality(team),
+                        ));
+                    }
+                    None => options.push((
+                        format!("{}: Human", player.name),
+                        BattleSetupOptions::Controller(team),
+                    )),
+                }
+            }
+            options.push(("Start".into(), BattleSetupOptions::Start));
+            options.push(("Back".into(), BattleSetupOptions::Back));
+            self.panel = Some(Panel::new(PanelConfig {
+                options,
+                font: wrapper.get_font(20.).await?,
+                top_left: wrapper.get_pos_vector(0.1, 0.2),
+                width: 300.,
+                background: wrapper.get_image("pixel.png".into()).await?,
+                text_size: 30.,
+            }));
+        }
+        Ok(None)
+    }
+    async fn event(
+        &mut self,
+        wrapper: &mut Wrapper<'_>,
+        event: &Event,
+    ) -> Result<Option<Transition>> {
+        if let Some(panel) = &mut self.panel {
+            match panel.event(wrapper, event) {
+                Some(BattleSetupOptions::Controller(team)) => {
+                    let player = &mut self.players[*team];
+                    player.ai = next_controller(player.ai);
+                    self.panel = None;
+                }
+                Some(BattleSetupOptions::Personality(team)) => {
+                    if let Some(config) = &mut self.players[*team].ai {
+                        config.personality = config.personality.next();
+                    }
+                    self.panel = None;
+                }
+                Some(BattleSetupOptions::Start) => {
+                    let controllers = self
+                        .players
+                        .iter()
+                        .map(|player| player.ai)
+                        .collect::<Vec<_>>();
+                    return Ok(Some(
+                        Game::start(&self.map_file, &controllers, wrapper).await,
+                    ));
+                }
+                Some(BattleSetupOptions::Back) => {
+                    return Ok(Some(Transition::Swap(Box::new(MapPicker::new()))))
+                }
+                None => {}
+            }
+        }
+        Ok(None)
+    }
+}
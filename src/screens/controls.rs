@@ -0,0 +1,153 @@
+use super::screen::{Screen, Transition};
+use crate::{
+    input::{Action, Binding, Keymap, ACTIONS},
+    panel::{Panel, PanelConfig},
+    saves::write_keymap,
+    Wrapper,
+};
+use async_trait::async_trait;
+use mergui::{FontStyle, MFont};
+use quicksilver::{
+    graphics::Color,
+    lifecycle::{Event, MouseButton},
+    Result,
+};
+
+pub enum ControlsOptions {
+    Rebind(Action),
+    Reset,
+    Back,
+}
+
+/// Lists what every action is bound to and lets the player pick a new key or button for it.
+pub struct ControlsScreen {
+    /// The action that gets bound to the next key or button that is pressed. A left click cancels.
+    rebinding: Option<Action>,
+    message: Option<String>,
+    font: Option<MFont>,
+    panel: Option<Panel<ControlsOptions>>,
+}
+
+impl ControlsScreen {
+    pub fn new() -> Self {
+        Self {
+            rebinding: None,
+            message: None,
+            font: None,
+            panel: None,
+        }
+    }
+    fn save(&mut self, keymap: &Keymap) {
+        self.message = write_keymap(keymap)
+            .err()
+            .map(|error| format!("Could not save the controls: {}", error));
+        self.panel = None;
+    }
+}
+
+#[async_trait(?Send)]
+impl Screen for ControlsScreen {
+    async fn draw(&mut self, wrapper: &mut Wrapper<'_>) -> Result<()> {
+        let text = match self.rebinding {
+            Some(action) => Some(format!(
+                "Press a key or button for {}, or click to cancel",
+                action.get_name()
+            )),
+            None => self.message.clone(),
+        };
+        if let (Some(font), Some(text)) = (&self.font, text) {
+            let style = FontStyle {
+                font: font.clone(),
+                location: wrapper.get_pos_vector(0.1, 0.05),
+                color: Color::WHITE,
+            };
+            style.draw(&mut wrapper.gfx, &text)?;
+        }
+        if let Some(panel) = &self.panel {
+            panel.draw([0., 0.].into(), wrapper)?;
+        }
+        Ok(())
+    }
+    async fn update(&mut self, wrapper: &mut Wrapper<'_>) -> Result<Option<Transition>> {
+        if let None = self.font {
+            self.font = Some(wrapper.get_font(20.).await?);
+        }
+        if let None = self.panel {
+            let keymap = &wrapper.keymap;
+            let mut options = ACTIONS
+                .iter()
+                .map(|action| {
+                    let bindings = keymap
+                        .get_bindings(*action)
+                        .iter()
+                        .map(|binding| String::from(*binding))
+                        .collect::<Vec<_>>();
+                    let bindings = if bindings.is_empty() {
+                        "Nothing".to_owned()
+                    } else {
+                        bindings.join(", ")
+                    };
+                    (
+                        format!("{}: {}", action.get_name(), bindings),
+                        ControlsOptions::Rebind(*action),
+                    )
+                })
+                .collect::<Vec<_>>();
+            options.push(("Reset to defaults".into(), ControlsOptions::Reset));
+            options.push(("Back".into(), ControlsOptions::Back));
+            self.panel = Some(Panel::new(PanelConfig {
+                options,
+                font: wrapper.get_font(20.).await?,
+                top_left: wrapper.get_pos_vector(0.1, 0.1),
+                width: 450.,
+                background: wrapper.get_image("pixel.png".into()).await?,
+                text_size: 30.,
+            }));
+        }
+        Ok(None)
+    }
+    async fn event(
+        &mut self,
+        wrapper: &mut Wrapper<'_>,
+        event: &Event,
+    ) -> Result<Option<Transition>> {
+        if let Some(action) = self.rebinding {
+            match Binding::from_event(event) {
+                Some((Binding::Mouse(MouseButton::Left), true)) => {
+                    self.rebinding = None;
+                    self.message = None;
+                }
+                Some((binding, true)) if binding.can_be_bound() => {
+                    self.rebinding = None;
+                    if let Some(required) = wrapper.keymap.would_leave_unbound(action, binding) {
+                        self.message = Some(format!(
+                            "{} is all {} has left, pick another",
+                            String::from(binding),
+                            required.get_name()
+                        ));
+                    } else {
+                        wrapper.keymap.bind(action, binding);
+                        self.save(&wrapper.keymap);
+                    }
+                }
+                _ => {}
+            }
+            return Ok(None);
+        }
+        if let Some(panel) = &mut self.panel {
+            match panel.event(wrapper, event) {
+                Some(ControlsOptions::Rebind(action)) => {
+                    self.rebinding = Some(*action);
+                    self.message = None;
+                }
+                Some(ControlsOptions::Reset) => {
+                    wrapper.keymap = Keymap::default();
+                    self.save(&wrapper.keymap);
+                }
+                Some(ControlsOptions::Back) => return Ok(Some(Transition::Pop)),
+                None => {}
+            }
+        }
+        Ok(None)
+    }
+}
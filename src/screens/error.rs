@@ -0,0 +1,74 @@
+use super::screen::{Screen, Transition};
+use crate::{
+    panel::{Panel, PanelConfig},
+    Wrapper,
+};
+use async_trait::async_trait;
+use mergui::{FontStyle, MFont};
+use quicksilver::{graphics::Color, lifecycle::Event, Result};
+
+pub enum ErrorOptions {
+    Back,
+}
+
+pub struct ErrorScreen {
+    pub message: String,
+    font: Option<MFont>,
+    panel: Option<Panel<ErrorOptions>>,
+}
+
+impl ErrorScreen {
+    pub fn new(message: String) -> Self {
+        Self {
+            message,
+            font: None,
+            panel: None,
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl Screen for ErrorScreen {
+    async fn draw(&mut self, wrapper: &mut Wrapper<'_>) -> Result<()> {
+        if let Some(font) = &self.font {
+            let style = FontStyle {
+                font: font.clone(),
+                location: wrapper.get_pos_vector(0.05, 0.3),
+                color: Color::RED,
+            };
+            style.draw(&mut wrapper.gfx, &self.message)?;
+        }
+        if let Some(panel) = &self.panel {
+            panel.draw([0., 0.].into(), wrapper)?;
+        }
+        Ok(())
+    }
+    async fn update(&mut self, wrapper: &mut Wrapper<'_>) -> Result<Option<Transition>> {
+        if let None = self.font {
+            self.font = Some(wrapper.get_font(20.).await?);
+        }
+        if let None = self.panel {
+            self.panel = Some(Panel::new(PanelConfig {
+                options: vec![("Back".into(), ErrorOptions::Back)],
+                font: wrapper.get_font(20.).await?,
+                top_left: wrapper.get_pos_vector(0.05, 0.5),
+                width: 100.,
+                background: wrapper.get_image("pixel.png".into()).await?,
+                text_size: 30.,
+            }));
+        }
+        Ok(None)
+    }
+    async fn event(
+        &mut self,
+        wrapper: &mut Wrapper<'_>,
+        event: &Event,
+    ) -> Result<Option<Transition>> {
+        if let Some(panel) = &mut self.panel {
+            if let Some(ErrorOptions::Back) = panel.event(wrapper, event) {
+                return Ok(Some(Transition::Pop));
+            }
+        }
+        Ok(None)
+    }
+}
@@ -1,20 +1,43 @@
-use super::screen::Screen;
+use super::{
+    battle_end::BattleEnd,
+    error::ErrorScreen,
+    pause::PauseMenu,
+    screen::{Screen, Transition},
+};
 use crate::{
-    character::CharacterContainer,
-    grid::{Grid, ParseableMap, TILE_SIZE},
+    assets::load_map,
+    camera::{edge_direction, Camera},
+    draw::{draw_characters, draw_grid},
     grid_pos_to_rectangle,
+    input::{Action, Sticks},
     panel::{Panel, PanelConfig},
+    saves::{clear_autosaves, write_autosave},
     Wrapper,
 };
 use async_trait::async_trait;
+use magic_wars::rules::{
+    ai::{self, AiConfig},
+    battle::Battle,
+    character::CharacterContainer,
+    combat,
+    data::GameData,
+    error::MapError,
+    grid::{Grid, TILE_SIZE},
+    save::SaveGame,
+    spells::Spell,
+    victory::objectives,
+};
+use mergui::{FontStyle, MFont};
 use quicksilver::{
     geom::{Rectangle, Transform, Vector},
     graphics::Color,
-    lifecycle::{Key, MouseButton},
-    load_file,
+    lifecycle::{MouseButton, ScrollDelta},
     mint::Vector2,
 };
-use std::{collections::HashSet, convert::TryFrom};
+use std::{
+    collections::{HashMap, HashSet},
+    convert::TryFrom,
+};
 
 #[derive(Clone, Copy, Eq, PartialEq, Hash)]
 pub enum Directions {
@@ -46,45 +69,152 @@ impl From<Directions> for Vector {
         }
     }
 }
-impl TryFrom<Key> for Directions {
+impl TryFrom<Action> for Directions {
     type Error = ();
-    fn try_from(k: Key) -> Result<Self, Self::Error> {
-        match k {
-            Key::W => Ok(Directions::Up),
-            Key::A => Ok(Directions::Left),
-            Key::S => Ok(Directions::Down),
-            Key::D => Ok(Directions::Right),
+    fn try_from(action: Action) -> Result<Self, Self::Error> {
+        match action {
+            Action::PanUp => Ok(Directions::Up),
+            Action::PanLeft => Ok(Directions::Left),
+            Action::PanDown => Ok(Directions::Down),
+            Action::PanRight => Ok(Directions::Right),
             _ => Err(()),
         }
     }
 }
 
+/// How far an action moves the tile cursor.
+fn tile_step(action: Action) -> Option<Vector2<i32>> {
+    match action {
+        Action::CursorUp => Some([0, -1].into()),
+        Action::CursorDown => Some([0, 1].into()),
+        Action::CursorLeft => Some([-1, 0].into()),
+        Action::CursorRight => Some([1, 0].into()),
+        _ => None,
+    }
+}
+
+/// How many updates the tile cursor waits before moving again while a stick is held.
+const STICK_REPEAT: u32 = 4;
+
+/// The entry after, or before if not `forward`, `current` in `options`.
+/// Starts at the first one if `current` isn't in there.
+fn cycle<T: Copy + PartialEq>(options: &[T], current: Option<T>, forward: bool) -> Option<T> {
+    let len = options.len().max(1);
+    let next = current
+        .and_then(|current| options.iter().position(|option| *option == current))
+        .map(|at| if forward { at + 1 } else { at + len - 1 })
+        .unwrap_or(0);
+    options.get(next % len).copied()
+}
+
+pub enum TurnOptions {
+    EndTurn,
+}
+
 pub enum AfterMoveOptions {
     Undo,
     Fight,
+    Cast,
     Stay,
 }
 pub enum InputState {
     Normal,
-    DrawingPath(u64, Vec<Vector2<i32>>),
+    DrawingPath(u64, DrawnPath),
     WaitingForCharacterMovement(u64, Vector2<i32>),
     SelectingActionAfterMove(Panel<AfterMoveOptions>, u64, Vector2<i32>),
     SelectingFight(u64, Vector2<i32>, Vec<(u64, Vector2<i32>)>),
+    SelectingSpell(Panel<String>, u64, Vector2<i32>),
+    SelectingSpellTarget(u64, Spell, Vec<Vector2<i32>>),
+}
+
+pub struct DrawnPath {
+    pub waypoints: Vec<Vector2<i32>>,
+    pub path: Vec<Vector2<i32>>,
+    pub reachable: HashMap<Vector2<i32>, usize>,
+}
+
+impl DrawnPath {
+    pub fn new(start: Vector2<i32>, reachable: HashMap<Vector2<i32>, usize>) -> Self {
+        Self {
+            waypoints: vec![start],
+            path: vec![start],
+            reachable,
+        }
+    }
+    fn find_route(
+        id: u64,
+        waypoints: &[Vector2<i32>],
+        goal: Vector2<i32>,
+        characters: &CharacterContainer,
+        grid: &Grid,
+    ) -> Option<Vec<Vector2<i32>>> {
+        let mut path = vec![*waypoints.first()?];
+        for next in waypoints.iter().skip(1).chain(std::iter::once(&goal)) {
+            let from = *path.last()?;
+            if from == *next {
+                continue;
+            }
+            let segment = characters.find_path(id, from, *next, grid)?;
+            path.extend(segment.into_iter().skip(1));
+        }
+        if characters.can_take_path(id, &path, grid) {
+            Some(path)
+        } else {
+            None
+        }
+    }
+    /// Makes the path go through `pos`, going back a step instead if `pos` was the previous waypoint.
+    pub fn add_waypoint(
+        &mut self,
+        id: u64,
+        pos: Vector2<i32>,
+        characters: &CharacterContainer,
+        grid: &Grid,
+    ) {
+        let len = self.waypoints.len();
+        if self.waypoints.last() == Some(&pos) {
+            return;
+        }
+        if len > 1 && self.waypoints[len - 2] == pos {
+            self.waypoints.pop();
+            return;
+        }
+        self.waypoints.push(pos);
+        if Self::find_route(id, &self.waypoints, pos, characters, grid).is_none() {
+            self.waypoints.pop();
+        }
+    }
+    /// Routes the path through all waypoints to `goal`.
+    /// If the waypoints make it too long, the shortest path from the character is used instead.
+    pub fn route_to(
+        &mut self,
+        id: u64,
+        goal: Vector2<i32>,
+        characters: &CharacterContainer,
+        grid: &Grid,
+    ) {
+        let last_waypoint = *self.waypoints.last().expect("Path has no start?");
+        let route = Self::find_route(id, &self.waypoints, goal, characters, grid)
+            .or_else(|| Self::find_route(id, &self.waypoints[..1], goal, characters, grid))
+            .or_else(|| Self::find_route(id, &self.waypoints, last_waypoint, characters, grid));
+        if let Some(route) = route {
+            self.path = route;
+        }
+    }
 }
 
 impl InputState {
     pub fn to_waiting_for_fight(&mut self) -> Result<(u64, Vec<Vector2<i32>>), ()> {
         match self {
-            InputState::DrawingPath(id, path) => {
+            InputState::DrawingPath(id, drawn) => {
                 let id = *id;
-                let last_place = path.last().unwrap().clone();
-                drop(path);
+                let last_place = drawn.path.last().unwrap().clone();
                 let old = std::mem::replace(
                     self,
                     InputState::WaitingForCharacterMovement(id, last_place),
                 );
-                if let InputState::DrawingPath(id, path) = old {
-                    Ok((id, path))
+                if let InputState::DrawingPath(id, drawn) = old {
+                    Ok((id, drawn.path))
                 } else {
                     unreachable!()
                 }
@@ -96,60 +226,312 @@ impl InputState {
 
 pub struct Game {
     pub moving: HashSet<Directions>,
-    pub translate: Vector,
-    pub grid: Grid,
+    pub camera: Camera,
+    /// Where the cursor was last while dragging the map with the middle mouse button.
+    pub drag_from: Option<Vector>,
+    pub cursor_in_window: bool,
+    pub battle: Battle,
     pub selected: InputState, //Option<(u64, Vec<Vector2<i32>>)>,
-    pub characters: CharacterContainer,
+    pub left_mouse_down: bool,
+    /// The left mouse button is held since it selected a unit, so letting go shouldn't move it yet.
+    pub just_selected: bool,
+    /// The tile picked with the keyboard. Moving the mouse hides it again.
+    pub tile_cursor: Option<Vector2<i32>>,
+    pub sticks: Sticks,
+    /// Updates left until holding the left stick moves the tile cursor again.
+    pub stick_cooldown: u32,
+    pub turn_panel: Option<Panel<TurnOptions>>,
+    pub map_file: String,
+    pub font: Option<MFont>,
+    /// The unit the AI is moving right now and who it attacks once it arrives.
+    pub ai_attack: Option<(u64, Option<u64>)>,
 }
 
 impl Game {
-    pub async fn new() -> Self {
-        let file = load_file("map.json")
-            .await
-            .expect("something has gone wrong");
-        let (grid, characters) = serde_json::from_slice::<ParseableMap>(&file)
-            .expect("couldn't parse")
-            .parse()
-            .expect("gone wrong");
+    /// `controllers` overrides who plays each team, the map decides for teams it doesn't cover.
+    pub async fn new(
+        map_file: &str,
+        controllers: &[Option<AiConfig>],
+        data: &GameData,
+        seed: u64,
+    ) -> Result<Self, MapError> {
+        let mut battle = Battle::new(load_map(map_file).await?, data, seed)?;
+        for (player, controller) in battle.turns.players.iter_mut().zip(controllers) {
+            player.ai = *controller;
+        }
+        let game = Self::from_battle(battle, map_file.to_owned());
+        game.autosave();
+        Ok(game)
+    }
+    /// Continues a saved battle.
+    pub fn from_save(save: SaveGame, data: &GameData) -> Result<Self, MapError> {
+        let map_file = save.map_file.clone();
+        Ok(Self::from_battle(save.restore(data)?, map_file))
+    }
+    fn from_battle(battle: Battle, map_file: String) -> Self {
         Self {
             moving: HashSet::new(),
-            translate: Vector::new(0, 0),
-            grid,
-            characters,
+            camera: Camera::new(),
+            drag_from: None,
+            cursor_in_window: false,
+            battle,
             selected: InputState::Normal,
+            left_mouse_down: false,
+            just_selected: false,
+            tile_cursor: None,
+            sticks: Sticks::new(),
+            stick_cooldown: 0,
+            turn_panel: None,
+            map_file,
+            font: None,
+            ai_attack: None,
+        }
+    }
+    /// Starts a battle on the given map, or shows why the map couldn't be loaded.
+    pub async fn start(
+        map_file: &str,
+        controllers: &[Option<AiConfig>],
+        wrapper: &Wrapper<'_>,
+    ) -> Transition {
+        match Game::new(map_file, controllers, &wrapper.data, wrapper.ticks).await {
+            Ok(game) => Transition::Swap(Box::new(game)),
+            Err(error) => Transition::Push(Box::new(ErrorScreen::new(error.to_string()))),
         }
     }
 }
 
 impl Game {
     fn cursor_pos_to_grid(&self, pos: Vector2<f32>) -> Vector2<i32> {
-        let raw_pos = Transform::translate(self.translate).inverse() * Vector::new(pos.x, pos.y);
-        [
-            (raw_pos.x / TILE_SIZE as f32).floor() as i32,
-            (raw_pos.y / TILE_SIZE as f32).floor() as i32,
-        ]
-        .into()
-    }
-    fn add_to_path(&mut self, grid_pos: Vector2<i32>) {
-        if let InputState::DrawingPath(id, path) = &mut self.selected {
-            if path.len() > 1
-                && path
-                    .get(path.len() - 2)
-                    .expect("Selected is not long enough???")
-                    == &grid_pos
-            {
-                path.pop();
-            } else if path.last().expect("Path was empty?") != &grid_pos {
-                let last = path.last().expect("...");
-                if (last.x - grid_pos.x).abs() <= 1
-                    && (last.y - grid_pos.y).abs() <= 1
-                    && (last.x == grid_pos.x || last.y == grid_pos.y)
-                    && self.characters.can_take_path(*id, path, &self.grid)
-                {
-                    if let None = self.characters.get_char_id_by_pos(grid_pos) {
-                        path.push(grid_pos);
+        self.camera.screen_to_grid(Vector::new(pos.x, pos.y))
+    }
+    /// The tile under the tile cursor if it is shown, otherwise the one under the mouse.
+    fn pointed_tile(&self, cursor_pos: Vector2<f32>) -> Vector2<i32> {
+        self.tile_cursor
+            .unwrap_or_else(|| self.cursor_pos_to_grid(cursor_pos))
+    }
+    /// Moves the tile cursor, extending the path through every tile it visits.
+    fn move_tile_cursor(&mut self, step: Vector2<i32>, wrapper: &Wrapper<'_>) {
+        let from = self.pointed_tile(wrapper.last_cursor_pos);
+        let to: Vector2<i32> = [from.x + step.x, from.y + step.y].into();
+        let to = if self.battle.grid.in_bounds(to) {
+            to
+        } else if self.battle.grid.in_bounds(from) {
+            from
+        } else {
+            [
+                from.x.max(0).min(self.battle.grid.width() - 1),
+                from.y.max(0).min(self.battle.grid.height() - 1),
+            ]
+            .into()
+        };
+        self.tile_cursor = Some(to);
+        let window_size = wrapper.window.size();
+        if !self.camera.shows(to, 1, window_size) {
+            self.camera.center_on(to, window_size);
+        }
+        self.update_path(to, true);
+    }
+    fn uses_tile_cursor(&self) -> bool {
+        match self.selected {
+            InputState::Normal
+            | InputState::DrawingPath(..)
+            | InputState::SelectingFight(..)
+            | InputState::SelectingSpellTarget(..) => true,
+            _ => false,
+        }
+    }
+    /// Moves the tile cursor to the next or previous unit that can still move, or target.
+    fn cycle_tile_cursor(&mut self, forward: bool, wrapper: &Wrapper<'_>) {
+        let current = self.pointed_tile(wrapper.last_cursor_pos);
+        let next = match &self.selected {
+            InputState::Normal => {
+                let team = self.battle.turns.active_player;
+                let mut units = self
+                    .battle
+                    .characters
+                    .characters
+                    .values()
+                    .filter(|character| character.team == team && !character.has_moved)
+                    .map(|character| (character.id, character.position))
+                    .collect::<Vec<_>>();
+                units.sort_by_key(|(id, _)| *id);
+                let ids = units.iter().map(|(id, _)| *id).collect::<Vec<_>>();
+                cycle(
+                    &ids,
+                    self.battle.characters.get_char_id_by_pos(current),
+                    forward,
+                )
+                .and_then(|id| units.iter().find(|(unit, _)| *unit == id))
+                .map(|(_, position)| *position)
+            }
+            InputState::SelectingFight(_, _, targets) => {
+                let positions = targets.iter().map(|(_, at)| *at).collect::<Vec<_>>();
+                cycle(&positions, Some(current), forward)
+            }
+            InputState::SelectingSpellTarget(_, _, targets) => {
+                cycle(targets, Some(current), forward)
+            }
+            _ => None,
+        };
+        if let Some(next) = next {
+            self.tile_cursor = Some(next);
+            self.camera.center_on(next, wrapper.window.size());
+        }
+    }
+    /// Selects the unit on `tile`, or attacks or casts at it when picking a target.
+    fn confirm(&mut self, tile: Vector2<i32>, wrapper: &Wrapper<'_>) -> Option<Transition> {
+        match &self.selected {
+            InputState::Normal => {
+                let id = self.battle.characters.get_char_id_by_pos(tile)?;
+                let reachable = self.battle.select(id).ok()?;
+                let window_size = wrapper.window.size();
+                if !self.camera.shows(tile, 1, window_size) {
+                    self.camera.center_on(tile, window_size);
+                }
+                self.selected = InputState::DrawingPath(id, DrawnPath::new(tile, reachable));
+                None
+            }
+            InputState::SelectingFight(id, _, targets) => {
+                let target = targets
+                    .iter()
+                    .find(|(_, loc)| loc == &tile)
+                    .map(|(id, _)| *id)?;
+                self.battle.attack(*id, target).ok()?;
+                self.selected = InputState::Normal;
+                self.check_victory()
+            }
+            InputState::SelectingSpellTarget(id, spell, targets) => {
+                if !targets.contains(&tile) {
+                    return None;
+                }
+                self.battle
+                    .cast(*id, spell, tile, &wrapper.data.character_types)
+                    .ok()?;
+                self.selected = InputState::Normal;
+                self.check_victory()
+            }
+            _ => None,
+        }
+    }
+    /// Moves the selected unit along the drawn path, if the path ends at `tile`.
+    /// A path that ends where it started keeps the unit in place, so it can still act.
+    fn finish_path(&mut self, tile: Vector2<i32>) {
+        if let InputState::DrawingPath(_, drawn) = &self.selected {
+            if drawn.path.last() == Some(&tile) {
+                let (id, path) = self.selected.to_waiting_for_fight().unwrap();
+                if self.battle.move_character(id, path).is_err() {
+                    self.selected = InputState::Normal;
+                }
+            }
+        }
+    }
+    fn cancel(&mut self) {
+        self.selected = InputState::Normal;
+        self.battle.undo();
+    }
+    /// Hands the turn over and autosaves, as long as nothing is still moving.
+    fn end_turn(&mut self) -> Option<Transition> {
+        self.battle.end_turn().ok()?;
+        self.turn_panel = None;
+        if let Some(transition) = self.check_victory() {
+            return Some(transition);
+        }
+        self.autosave();
+        None
+    }
+    /// Snapshots the battle at the start of a turn. Not being able to save shouldn't stop the game.
+    fn autosave(&self) {
+        let _ = write_autosave(&SaveGame::new(&self.battle, &self.map_file));
+    }
+    fn check_victory(&self) -> Option<Transition> {
+        self.battle.winner().map(|winner| {
+            let _ = clear_autosaves();
+            Transition::Swap(Box::new(BattleEnd::new(
+                self.battle.turns.players[winner].name.clone(),
+                self.battle.turns.turn,
+                self.map_file.clone(),
+                self.battle
+                    .turns
+                    .players
+                    .iter()
+                    .map(|player| player.ai)
+                    .collect(),
+            )))
+        })
+    }
+    /// Opens the pause menu, unless something is still happening that a save couldn't capture.
+    fn pause(&mut self) -> Option<Transition> {
+        let is_idle = match self.selected {
+            InputState::Normal => true,
+            _ => false,
+        };
+        if !is_idle || self.battle.characters.is_moving() || self.ai_attack.is_some() {
+            return None;
+        }
+        self.moving.clear();
+        self.sticks = Sticks::new();
+        self.camera.stop();
+        self.drag_from = None;
+        Some(Transition::Push(Box::new(PauseMenu::new(SaveGame::new(
+            &self.battle,
+            &self.map_file,
+        )))))
+    }
+    fn is_ai_turn(&self) -> bool {
+        self.battle.turns.get_active_player().ai.is_some()
+    }
+    /// Lets the AI act with its next unit, ending its turn once all of them are done.
+    fn play_ai(&mut self, window_size: Vector) -> Option<Transition> {
+        if self.battle.characters.is_moving() {
+            return None;
+        }
+        if let Some((id, target)) = self.ai_attack.take() {
+            match target {
+                Some(target) => {
+                    if self.battle.attack(id, target).is_err() {
+                        self.battle.finish_move();
                     }
                 }
+                None => self.battle.finish_move(),
+            }
+            return self.check_victory();
+        }
+        let team = self.battle.turns.active_player;
+        let config = self.battle.turns.get_active_player().ai?;
+        let objectives = objectives(&self.battle.win_conditions, &self.battle.characters, team);
+        let plan = ai::plan_move(
+            &self.battle.characters,
+            &self.battle.grid,
+            team,
+            config,
+            &objectives,
+            &mut self.battle.rng,
+        );
+        let moved = plan.map(|plan| {
+            let (unit, target) = (plan.unit, plan.target);
+            let destination = plan.path.last().copied();
+            let moved = self.battle.move_character(unit, plan.path);
+            if let (Ok(_), Some(destination)) = (&moved, destination) {
+                self.camera.center_on(destination, window_size);
+            }
+            moved.map(|_| (unit, target))
+        });
+        match moved {
+            Some(Ok(attack)) => {
+                self.ai_attack = Some(attack);
+                None
+            }
+            _ => self.end_turn(),
+        }
+    }
+    fn update_path(&mut self, grid_pos: Vector2<i32>, add_waypoint: bool) {
+        if let InputState::DrawingPath(id, drawn) = &mut self.selected {
+            if add_waypoint {
+                drawn.add_waypoint(*id, grid_pos, &self.battle.characters, &self.battle.grid);
+            }
+            drawn.route_to(*id, grid_pos, &self.battle.characters, &self.battle.grid);
+            if drawn.path.len() > 1 {
+                self.just_selected = false;
             }
         }
     }
@@ -158,9 +540,15 @@ impl Game {
 #[async_trait(?Send)]
 impl Screen for Game {
     async fn draw(&mut self, wrapper: &mut crate::Wrapper<'_>) -> quicksilver::Result<()> {
-        self.grid.draw(wrapper);
-        if let InputState::DrawingPath(_, path) = &self.selected {
-            for v in path {
+        wrapper.gfx.set_transform(self.camera.get_transform());
+        draw_grid(&self.battle.grid, wrapper);
+        if let InputState::DrawingPath(_, drawn) = &self.selected {
+            for v in drawn.reachable.keys() {
+                wrapper
+                    .gfx
+                    .fill_rect(&grid_pos_to_rectangle(*v), Color::WHITE.with_alpha(0.4));
+            }
+            for v in &drawn.path {
                 wrapper.gfx.fill_rect(
                     &Rectangle::new(
                         Vector::new(v.x * TILE_SIZE, v.y * TILE_SIZE),
@@ -170,16 +558,71 @@ impl Screen for Game {
                 );
             }
         }
-        self.characters.draw(wrapper)?;
+        draw_characters(&self.battle.characters, wrapper)?;
+        if let Some(tile) = self.tile_cursor {
+            wrapper
+                .gfx
+                .stroke_rect(&grid_pos_to_rectangle(tile), Color::BLACK);
+        }
         match &self.selected {
-            InputState::SelectingFight(_, _, targets) => {
-                for (_, target) in targets {
+            InputState::SelectingFight(id, _, targets) => {
+                for (target_id, target) in targets {
+                    let rectangle = grid_pos_to_rectangle(*target);
                     wrapper
                         .gfx
-                        .fill_rect(&grid_pos_to_rectangle(target.clone()), Color::ORANGE);
+                        .fill_rect(&rectangle, Color::ORANGE.with_alpha(0.7));
+                    let attacker = self.battle.characters.characters.get(id);
+                    let defender = self.battle.characters.characters.get(target_id);
+                    if let (Some(attacker), Some(defender), Some(font)) =
+                        (attacker, defender, &self.font)
+                    {
+                        let preview = combat::preview(attacker, defender, &self.battle.grid);
+                        let lines = [
+                            format!("{}%", preview.hit_chance),
+                            format!("{} dmg", preview.damage),
+                            format!("{:+} def", preview.defense_bonus),
+                        ];
+                        for (line_number, line) in lines.iter().enumerate() {
+                            FontStyle {
+                                font: font.clone(),
+                                location: rectangle.pos
+                                    + Vector::new(2., 14. + 14. * line_number as f32),
+                                color: Color::BLACK,
+                            }
+                            .draw(&mut wrapper.gfx, line)?;
+                        }
+                    }
+                }
+            }
+            InputState::SelectingSpellTarget(_, spell, targets) => {
+                for target in targets {
+                    wrapper.gfx.fill_rect(
+                        &grid_pos_to_rectangle(*target),
+                        Color::PURPLE.with_alpha(0.4),
+                    );
+                }
+                let cursor_pos = self.pointed_tile(wrapper.last_cursor_pos);
+                if targets.contains(&cursor_pos) {
+                    for tile in spell.area.get_tiles(cursor_pos) {
+                        wrapper.gfx.fill_rect(
+                            &grid_pos_to_rectangle(tile),
+                            Color::MAGENTA.with_alpha(0.6),
+                        );
+                    }
+                }
+            }
+            _ => {}
+        }
+        wrapper.gfx.set_transform(Transform::IDENTITY);
+        let no_translate = Vector::new(0., 0.);
+        match &self.selected {
+            InputState::SelectingActionAfterMove(x, _, _) => x.draw(no_translate, wrapper)?,
+            InputState::SelectingSpell(x, _, _) => x.draw(no_translate, wrapper)?,
+            InputState::Normal => {
+                if let (Some(panel), false) = (&self.turn_panel, self.is_ai_turn()) {
+                    panel.draw(no_translate, wrapper)?
                 }
             }
-            InputState::SelectingActionAfterMove(x, _, _) => x.draw(self.translate, wrapper)?,
             _ => {}
         }
 
@@ -188,32 +631,83 @@ impl Screen for Game {
     async fn update(
         &mut self,
         wrapper: &mut crate::Wrapper<'_>,
-    ) -> quicksilver::Result<Option<Box<dyn Screen>>> {
-        let mut translate = self.translate.clone();
+    ) -> quicksilver::Result<Option<Transition>> {
+        let mut direction = Vector::new(0., 0.);
         self.moving.iter().copied().for_each(|v| {
-            translate += Vector::from(v).times(Vector::new(10, 10));
+            direction -= Vector::from(v);
         });
-        if translate.x > 0. {
-            translate.x = 0.;
+        if wrapper.settings.edge_scrolling && self.cursor_in_window && self.drag_from.is_none() {
+            let cursor_pos = wrapper.last_cursor_pos;
+            direction += edge_direction(
+                Vector::new(cursor_pos.x, cursor_pos.y),
+                wrapper.window.size(),
+            );
         }
-        if translate.y > 0. {
-            translate.y = 0.
+        direction += self.sticks.camera;
+        let direction = Vector::new(direction.x.max(-1.).min(1.), direction.y.max(-1.).min(1.));
+        let mut camera = self.camera.clone();
+        camera.scroll(
+            direction,
+            wrapper.settings.scroll_speed.get_pixels_per_update(),
+            wrapper.settings.scroll_acceleration.get_step(),
+        );
+        camera.clamp(&self.battle.grid, wrapper.window.size());
+        if self.camera != camera {
+            self.camera = camera;
+            if let None = self.tile_cursor {
+                let cursor_pos = self.cursor_pos_to_grid(wrapper.last_cursor_pos);
+                self.update_path(cursor_pos, self.left_mouse_down);
+            }
         }
-        if self.translate != translate {
-            let cursor_pos = self.cursor_pos_to_grid(wrapper.last_cursor_pos);
-            self.add_to_path(cursor_pos);
+        match self.sticks.get_cursor_step() {
+            Some(step) if self.uses_tile_cursor() && !self.is_ai_turn() => {
+                if self.stick_cooldown == 0 {
+                    self.move_tile_cursor(step.into(), wrapper);
+                    self.stick_cooldown = STICK_REPEAT;
+                } else {
+                    self.stick_cooldown -= 1;
+                }
+            }
+            _ => self.stick_cooldown = 0,
         }
-        self.translate = translate;
-        wrapper.gfx.set_transform(Transform::translate(translate));
-        if self.characters.update()? {
+        if let None = self.font {
+            self.font = Some(wrapper.get_font(12.).await?);
+        }
+        if let None = self.turn_panel {
+            let width = 200.;
+            self.turn_panel = Some(Panel::new(PanelConfig {
+                options: vec![(
+                    format!("End turn ({})", self.battle.turns.get_active_player().name),
+                    TurnOptions::EndTurn,
+                )],
+                font: wrapper.get_font(20.).await?,
+                top_left: Vector::new(wrapper.window.size().x - width - 10., 20.),
+                width,
+                background: wrapper.get_image("pixel.png".into()).await?,
+                text_size: 30.,
+            }));
+        }
+        if self.battle.characters.update() {
+            if let Some(transition) = self.check_victory() {
+                return Ok(Some(transition));
+            }
             if let InputState::WaitingForCharacterMovement(id, at) = self.selected {
+                let mut options = vec![("Fight".into(), AfterMoveOptions::Fight)];
+                let can_cast = self
+                    .battle
+                    .characters
+                    .characters
+                    .get(&id)
+                    .map(|character| !wrapper.data.spells.castable_by(character).is_empty())
+                    .unwrap_or(false);
+                if can_cast {
+                    options.push(("Cast".into(), AfterMoveOptions::Cast));
+                }
+                options.push(("Stay".into(), AfterMoveOptions::Stay));
+                options.push(("Undo".into(), AfterMoveOptions::Undo));
                 self.selected = InputState::SelectingActionAfterMove(
                     Panel::new(PanelConfig {
-                        options: vec![
-                            ("Fight".into(), AfterMoveOptions::Fight),
-                            ("Stay".into(), AfterMoveOptions::Stay),
-                            ("Undo".into(), AfterMoveOptions::Undo),
-                        ],
+                        options,
                         font: wrapper.get_font(20.).await?,
                         top_left: Vector::new(10., 20.),
                         width: 50.,
@@ -225,85 +719,232 @@ impl Screen for Game {
                 );
             }
         }
+        if self.is_ai_turn() {
+            return Ok(self.play_ai(wrapper.window.size()));
+        }
         Ok(None)
     }
+    async fn on_enter(&mut self, wrapper: &mut Wrapper<'_>) -> quicksilver::Result<()> {
+        let sprites = wrapper.data.sprites().cloned().collect::<Vec<_>>();
+        for sprite in sprites {
+            wrapper.get_image(sprite).await?;
+        }
+        Ok(())
+    }
     async fn event(
         &mut self,
         wrapper: &mut Wrapper<'_>,
         event: &quicksilver::lifecycle::Event,
-    ) -> quicksilver::Result<Option<Box<dyn Screen>>> {
+    ) -> quicksilver::Result<Option<Transition>> {
+        if let quicksilver::lifecycle::Event::ScrollInput(delta) = event {
+            let amount = match delta {
+                ScrollDelta::Lines(amount) => amount.y,
+                ScrollDelta::Pixels(amount) => amount.y,
+            };
+            if amount != 0. {
+                let cursor_pos = wrapper.last_cursor_pos;
+                self.camera.zoom(
+                    amount.signum() as i32,
+                    Vector::new(cursor_pos.x, cursor_pos.y),
+                );
+                self.camera.clamp(&self.battle.grid, wrapper.window.size());
+                if let None = self.tile_cursor {
+                    let grid_pos = self.cursor_pos_to_grid(cursor_pos);
+                    self.update_path(grid_pos, self.left_mouse_down);
+                }
+            }
+            return Ok(None);
+        }
+        self.sticks.event(event);
+        let action = wrapper.keymap.action_of(event);
+        if let Some((Action::DragPan, is_down)) = action {
+            self.drag_from = if is_down && wrapper.settings.drag_panning {
+                let cursor_pos = wrapper.last_cursor_pos;
+                Some(Vector::new(cursor_pos.x, cursor_pos.y))
+            } else {
+                None
+            };
+            return Ok(None);
+        }
+        match event {
+            quicksilver::lifecycle::Event::PointerMoved(x) => {
+                self.cursor_in_window = true;
+                let loc = Vector::new(x.location().x, x.location().y);
+                if let Some(from) = self.drag_from {
+                    self.drag_from = Some(loc);
+                    self.camera.pan(from - loc);
+                    self.camera.clamp(&self.battle.grid, wrapper.window.size());
+                }
+            }
+            quicksilver::lifecycle::Event::PointerLeft(_) => {
+                self.cursor_in_window = false;
+                self.drag_from = None;
+            }
+            _ => {}
+        }
+        if self.is_ai_turn() && action.is_none() {
+            return Ok(None);
+        }
         if let InputState::SelectingActionAfterMove(panel, id, location) = &mut self.selected {
             if let Some(chosen) = panel.event(wrapper, event) {
                 match chosen {
                     AfterMoveOptions::Undo => {
-                        self.characters.undo();
+                        self.battle.undo();
                         self.selected = InputState::Normal;
                     }
                     AfterMoveOptions::Fight => {
-                        let in_range = self.characters.get_char_ids_in_range_of(*id);
+                        let in_range = self
+                            .battle
+                            .characters
+                            .get_char_ids_in_range_of(*id, &self.battle.grid);
                         self.selected = InputState::SelectingFight(*id, *location, in_range);
                     }
+                    AfterMoveOptions::Cast => {
+                        let (id, location) = (*id, *location);
+                        let options = match self.battle.characters.characters.get(&id) {
+                            Some(character) => wrapper
+                                .data
+                                .spells
+                                .castable_by(character)
+                                .into_iter()
+                                .map(|spell| {
+                                    (
+                                        format!("{} ({} mana)", spell.name, spell.cost),
+                                        spell.name.clone(),
+                                    )
+                                })
+                                .collect::<Vec<_>>(),
+                            None => Vec::new(),
+                        };
+                        if options.is_empty() {
+                            return Ok(None);
+                        }
+                        self.selected = InputState::SelectingSpell(
+                            Panel::new(PanelConfig {
+                                options,
+                                font: wrapper.get_font(20.).await?,
+                                top_left: Vector::new(10., 20.),
+                                width: 150.,
+                                background: wrapper.get_image("pixel.png".into()).await?,
+                                text_size: 30.,
+                            }),
+                            id,
+                            location,
+                        );
+                    }
                     AfterMoveOptions::Stay => {
                         self.selected = InputState::Normal;
-                        self.characters.finalize();
+                        self.battle.finish_move();
                     }
                 }
                 return Ok(None);
             }
         }
+        if let InputState::SelectingSpell(panel, id, _) = &mut self.selected {
+            if let Some(chosen) = panel.event(wrapper, event) {
+                if let Some(spell) = wrapper.data.spells.get(chosen).cloned() {
+                    let targets =
+                        spell.get_targets(*id, &self.battle.characters, &self.battle.grid);
+                    self.selected = InputState::SelectingSpellTarget(*id, spell, targets);
+                }
+                return Ok(None);
+            }
+        }
+        if let (InputState::Normal, Some(panel), None) =
+            (&self.selected, &mut self.turn_panel, action)
+        {
+            if let Some(TurnOptions::EndTurn) = panel.event(wrapper, event) {
+                return Ok(self.end_turn());
+            }
+        }
         match event {
             quicksilver::lifecycle::Event::PointerInput(x) => {
                 if x.button() == MouseButton::Left {
+                    self.left_mouse_down = x.is_down();
+                    self.tile_cursor = None;
+                    let cursor_pos = self.cursor_pos_to_grid(wrapper.last_cursor_pos.clone());
                     if x.is_down() {
-                        match &self.selected {
-                            InputState::DrawingPath(_, _) => {
-                                let (id, path) = self.selected.to_waiting_for_fight().unwrap();
-                                self.characters.move_character(id, path);
-                            }
-                            InputState::Normal => {
-                                if self.characters.is_moving() {
-                                    return Ok(None);
-                                }
-                                let pos = self.cursor_pos_to_grid(wrapper.last_cursor_pos.clone());
-                                if let Some(id) = self.characters.get_char_id_by_pos(pos) {
-                                    if self.characters.can_move(id) {
-                                        self.selected = InputState::DrawingPath(id, vec![pos])
-                                    }
-                                }
-                            }
-                            InputState::SelectingFight(_, _, targets) => {
-                                let cursor_pos =
-                                    self.cursor_pos_to_grid(wrapper.last_cursor_pos.clone());
-                                if let Some(_) = targets
-                                    .iter()
-                                    .find(|(_, loc)| loc == &cursor_pos)
-                                    .map(|(id, _)| id)
-                                {
-                                    self.selected = InputState::Normal
-                                }
+                        let was_normal = match self.selected {
+                            InputState::Normal => true,
+                            _ => false,
+                        };
+                        let transition = self.confirm(cursor_pos, wrapper);
+                        self.just_selected = match self.selected {
+                            InputState::DrawingPath(..) => was_normal,
+                            _ => false,
+                        };
+                        return Ok(transition);
+                    }
+                    if self.just_selected {
+                        self.just_selected = false;
+                        if let InputState::DrawingPath(_, drawn) = &self.selected {
+                            if drawn.path.len() == 1 {
+                                return Ok(None);
                             }
-                            _ => {}
                         }
                     }
-                } else if x.button() == MouseButton::Right && x.is_down() {
-                    self.selected = InputState::Normal;
-                    self.characters.undo();
+                    self.finish_path(cursor_pos);
                 }
             }
             quicksilver::lifecycle::Event::PointerMoved(x) => {
+                self.tile_cursor = None;
                 let loc = x.location();
                 let grid_pos = self.cursor_pos_to_grid(loc);
-                self.add_to_path(grid_pos);
+                self.update_path(grid_pos, self.left_mouse_down);
             }
-            quicksilver::lifecycle::Event::KeyboardInput(x) => {
-                if x.is_down() {
-                    if let Ok(key) = Directions::try_from(x.key()) {
-                        self.moving.insert(key);
-                    }
+            _ => {}
+        }
+        let (action, is_down) = match action {
+            Some(action) => action,
+            None => return Ok(None),
+        };
+        if let Ok(direction) = Directions::try_from(action) {
+            if is_down {
+                self.moving.insert(direction);
+            } else {
+                self.moving.remove(&direction);
+            }
+            return Ok(None);
+        }
+        if !is_down {
+            return Ok(None);
+        }
+        if let Action::Cancel = action {
+            return Ok(match self.selected {
+                InputState::Normal => self.pause(),
+                _ => {
+                    self.cancel();
+                    None
+                }
+            });
+        }
+        if self.is_ai_turn() {
+            return Ok(None);
+        }
+        if let Action::Undo = action {
+            self.cancel();
+            return Ok(None);
+        }
+        if !self.uses_tile_cursor() {
+            return Ok(None);
+        }
+        if let Some(step) = tile_step(action) {
+            self.move_tile_cursor(step, wrapper);
+        }
+        match action {
+            Action::NextUnit => self.cycle_tile_cursor(true, wrapper),
+            Action::PreviousUnit => self.cycle_tile_cursor(false, wrapper),
+            Action::Confirm => {
+                let tile = self.pointed_tile(wrapper.last_cursor_pos);
+                if let InputState::DrawingPath(..) = self.selected {
+                    self.finish_path(tile);
                 } else {
-                    if let Ok(key) = Directions::try_from(x.key()) {
-                        self.moving.remove(&key);
-                    }
+                    return Ok(self.confirm(tile, wrapper));
+                }
+            }
+            Action::EndTurn => {
+                if let InputState::Normal = self.selected {
+                    return Ok(self.end_turn());
                 }
             }
             _ => {}
@@ -0,0 +1,123 @@
+use super::{
+    error::ErrorScreen,
+    game::Game,
+    map_picker::MapPicker,
+    screen::{Screen, Transition},
+    settings::SettingsScreen,
+};
+use crate::{
+    panel::{Panel, PanelConfig},
+    saves::{read_latest_autosave, read_save, MANUAL_SAVE},
+    Wrapper,
+};
+use async_trait::async_trait;
+use mergui::{FontStyle, MFont};
+use quicksilver::{graphics::Color, lifecycle::Event, Result};
+
+pub enum MainMenuOptions {
+    Continue,
+    NewGame,
+    Load,
+    Settings,
+    Quit,
+}
+
+pub struct MainMenu {
+    font: Option<MFont>,
+    panel: Option<Panel<MainMenuOptions>>,
+}
+
+impl MainMenu {
+    pub fn new() -> Self {
+        Self {
+            font: None,
+            panel: None,
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl Screen for MainMenu {
+    async fn draw(&mut self, wrapper: &mut Wrapper<'_>) -> Result<()> {
+        if let Some(font) = &self.font {
+            let style = FontStyle {
+                font: font.clone(),
+                location: wrapper.get_pos_vector(0.1, 0.2),
+                color: Color::WHITE,
+            };
+            style.draw(&mut wrapper.gfx, "Dead wars")?;
+        }
+        if let Some(panel) = &self.panel {
+            panel.draw([0., 0.].into(), wrapper)?;
+        }
+        Ok(())
+    }
+    async fn update(&mut self, wrapper: &mut Wrapper<'_>) -> Result<Option<Transition>> {
+        if let None = self.font {
+            self.font = Some(wrapper.get_font(60.).await?);
+        }
+        if let None = self.panel {
+            let mut options = Vec::new();
+            if read_latest_autosave().is_some() {
+                options.push(("Continue".into(), MainMenuOptions::Continue));
+            }
+            options.push(("New game".into(), MainMenuOptions::NewGame));
+            if read_save(MANUAL_SAVE).is_ok() {
+                options.push(("Load".into(), MainMenuOptions::Load));
+            }
+            options.push(("Settings".into(), MainMenuOptions::Settings));
+            options.push(("Quit".into(), MainMenuOptions::Quit));
+            self.panel = Some(Panel::new(PanelConfig {
+                options,
+                font: wrapper.get_font(20.).await?,
+                top_left: wrapper.get_pos_vector(0.1, 0.4),
+                width: 150.,
+                background: wrapper.get_image("pixel.png".into()).await?,
+                text_size: 30.,
+            }));
+        }
+        Ok(None)
+    }
+    async fn on_enter(&mut self, _wrapper: &mut Wrapper<'_>) -> Result<()> {
+        // A save might have been made since the menu was built.
+        self.panel = None;
+        Ok(())
+    }
+    async fn event(
+        &mut self,
+        wrapper: &mut Wrapper<'_>,
+        event: &Event,
+    ) -> Result<Option<Transition>> {
+        if let Some(panel) = &mut self.panel {
+            if let Some(chosen) = panel.event(wrapper, event) {
+                return Ok(Some(match chosen {
+                    MainMenuOptions::Continue => match read_latest_autosave() {
+                        Some(save) => match Game::from_save(save, &wrapper.data) {
+                            Ok(game) => Transition::Push(Box::new(game)),
+                            Err(error) => {
+                                Transition::Push(Box::new(ErrorScreen::new(error.to_string())))
+                            }
+                        },
+                        None => return Ok(None),
+                    },
+                    MainMenuOptions::NewGame => Transition::Push(Box::new(MapPicker::new())),
+                    MainMenuOptions::Load => {
+                        let game = read_save(MANUAL_SAVE)
+                            .map_err(|error| error.to_string())
+                            .and_then(|save| {
+                                Game::from_save(save, &wrapper.data)
+                                    .map_err(|error| error.to_string())
+                            });
+                        match game {
+                            Ok(game) => Transition::Push(Box::new(game)),
+                            Err(error) => Transition::Push(Box::new(ErrorScreen::new(error))),
+                        }
+                    }
+                    MainMenuOptions::Settings => Transition::Push(Box::new(SettingsScreen::new())),
+                    MainMenuOptions::Quit => Transition::Quit,
+                }));
+            }
+        }
+        Ok(None)
+    }
+}
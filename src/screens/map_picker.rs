@@ -0,0 +1,153 @@
+use super::{
+    battle_setup::BattleSetup,
+    error::ErrorScreen,
+    screen::{Screen, Transition},
+};
+use crate::{
+    assets::load_map,
+    panel::{Panel, PanelConfig},
+    Wrapper,
+};
+use async_trait::async_trait;
+use magic_wars::rules::{error::MapError, turn::Player};
+use quicksilver::{lifecycle::Event, load_file, Result};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct MapEntry {
+    pub name: String,
+    pub file: String,
+}
+
+pub struct MapDetails {
+    pub width: usize,
+    pub height: usize,
+    pub players: Vec<Player>,
+}
+
+/// A map from the manifest, or why it couldn't be loaded so it can be shown instead.
+pub struct MapInfo {
+    pub entry: MapEntry,
+    pub details: std::result::Result<MapDetails, MapError>,
+}
+
+impl MapInfo {
+    async fn load(entry: MapEntry) -> Self {
+        let details = load_map(&entry.file).await.map(|map| MapDetails {
+            width: map
+                .tiles
+                .first()
+                .map(|row| row.chars().count())
+                .unwrap_or(0),
+            height: map.tiles.len(),
+            players: map.players,
+        });
+        Self { entry, details }
+    }
+}
+
+async fn load_manifest() -> std::result::Result<Vec<MapEntry>, MapError> {
+    let manifest = load_file("maps.json")
+        .await
+        .map_err(|error| MapError::CouldNotLoad(error.to_string()))?;
+    Ok(serde_json::from_slice(&manifest)?)
+}
+
+pub enum MapPickerOptions {
+    Map(usize),
+    Back,
+}
+
+pub struct MapPicker {
+    maps: Vec<MapInfo>,
+    panel: Option<Panel<MapPickerOptions>>,
+}
+
+impl MapPicker {
+    pub fn new() -> Self {
+        Self {
+            maps: Vec::new(),
+            panel: None,
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl Screen for MapPicker {
+    async fn draw(&mut self, wrapper: &mut Wrapper<'_>) -> Result<()> {
+        if let Some(panel) = &self.panel {
+            panel.draw([0., 0.].into(), wrapper)?;
+        }
+        Ok(())
+    }
+    async fn update(&mut self, wrapper: &mut Wrapper<'_>) -> Result<Option<Transition>> {
+        if let None = self.panel {
+            let entries = match load_manifest().await {
+                Ok(entries) => entries,
+                Err(error) => {
+                    return Ok(Some(Transition::Swap(Box::new(ErrorScreen::new(
+                        error.to_string(),
+                    )))))
+                }
+            };
+            self.maps.clear();
+            for entry in entries {
+                self.maps.push(MapInfo::load(entry).await);
+            }
+            let mut options = self
+                .maps
+                .iter()
+                .enumerate()
+                .map(|(key, map)| {
+                    let text = match &map.details {
+                        Ok(details) => format!(
+                            "{} ({}x{}, {} players)",
+                            map.entry.name,
+                            details.width,
+                            details.height,
+                            details.players.len()
+                        ),
+                        Err(_) => format!("{} (broken)", map.entry.name),
+                    };
+                    (text, MapPickerOptions::Map(key))
+                })
+                .collect::<Vec<_>>();
+            options.push(("Back".into(), MapPickerOptions::Back));
+            self.panel = Some(Panel::new(PanelConfig {
+                options,
+                font: wrapper.get_font(20.).await?,
+                top_left: wrapper.get_pos_vector(0.1, 0.2),
+                width: 400.,
+                background: wrapper.get_image("pixel.png".into()).await?,
+                text_size: 30.,
+            }));
+        }
+        Ok(None)
+    }
+    async fn event(
+        &mut self,
+        wrapper: &mut Wrapper<'_>,
+        event: &Event,
+    ) -> Result<Option<Transition>> {
+        if let Some(panel) = &mut self.panel {
+            match panel.event(wrapper, event) {
+                Some(MapPickerOptions::Map(key)) => {
+                    let map = &self.maps[*key];
+                    return Ok(Some(match &map.details {
+                        Ok(details) => Transition::Swap(Box::new(BattleSetup::new(
+                            map.entry.file.clone(),
+                            details.players.clone(),
+                        ))),
+                        Err(error) => Transition::Push(Box::new(ErrorScreen::new(format!(
+                            "{}: {}",
+                            map.entry.name, error
+                        )))),
+                    }));
+                }
+                Some(MapPickerOptions::Back) => return Ok(Some(Transition::Pop)),
+                None => {}
+            }
+        }
+        Ok(None)
+    }
+}
@@ -1,2 +1,11 @@
+pub mod battle_end;
+pub mod battle_setup;
+pub mod controls;
+pub mod error;
 pub mod game;
+pub mod main_menu;
+pub mod map_picker;
+pub mod pause;
 pub mod screen;
+pub mod settings;
+pub mod stack;
@@ -0,0 +1,139 @@
+use super::{
+    error::ErrorScreen,
+    game::Game,
+    screen::{Screen, Transition},
+};
+use crate::{
+    input::Action,
+    panel::{Panel, PanelConfig},
+    saves::{read_save, write_save, MANUAL_SAVE},
+    Wrapper,
+};
+use async_trait::async_trait;
+use magic_wars::rules::save::SaveGame;
+use mergui::{FontStyle, MFont};
+use quicksilver::{
+    geom::{Rectangle, Vector},
+    graphics::Color,
+    lifecycle::Event,
+    Result,
+};
+
+pub enum PauseOptions {
+    Resume,
+    Save,
+    Load,
+    MainMenu,
+}
+
+/// Shown over a battle, holds a snapshot of it taken when the game got paused.
+pub struct PauseMenu {
+    save: SaveGame,
+    message: Option<String>,
+    font: Option<MFont>,
+    panel: Option<Panel<PauseOptions>>,
+}
+
+impl PauseMenu {
+    pub fn new(save: SaveGame) -> Self {
+        Self {
+            save,
+            message: None,
+            font: None,
+            panel: None,
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl Screen for PauseMenu {
+    async fn draw(&mut self, wrapper: &mut Wrapper<'_>) -> Result<()> {
+        let size = wrapper.window.size();
+        wrapper.gfx.fill_rect(
+            &Rectangle::new(Vector::new(0., 0.), size),
+            Color::BLACK.with_alpha(0.6),
+        );
+        if let (Some(font), Some(message)) = (&self.font, &self.message) {
+            let style = FontStyle {
+                font: font.clone(),
+                location: wrapper.get_pos_vector(0.1, 0.15),
+                color: Color::WHITE,
+            };
+            style.draw(&mut wrapper.gfx, message)?;
+        }
+        if let Some(panel) = &self.panel {
+            panel.draw([0., 0.].into(), wrapper)?;
+        }
+        Ok(())
+    }
+    async fn update(&mut self, wrapper: &mut Wrapper<'_>) -> Result<Option<Transition>> {
+        if let None = self.font {
+            self.font = Some(wrapper.get_font(20.).await?);
+        }
+        if let None = self.panel {
+            let mut options = vec![
+                ("Resume".into(), PauseOptions::Resume),
+                ("Save".into(), PauseOptions::Save),
+            ];
+            if read_save(MANUAL_SAVE).is_ok() {
+                options.push(("Load".into(), PauseOptions::Load));
+            }
+            options.push(("Main menu".into(), PauseOptions::MainMenu));
+            self.panel = Some(Panel::new(PanelConfig {
+                options,
+                font: wrapper.get_font(20.).await?,
+                top_left: wrapper.get_pos_vector(0.1, 0.2),
+                width: 150.,
+                background: wrapper.get_image("pixel.png".into()).await?,
+                text_size: 30.,
+            }));
+        }
+        Ok(None)
+    }
+    async fn event(
+        &mut self,
+        wrapper: &mut Wrapper<'_>,
+        event: &Event,
+    ) -> Result<Option<Transition>> {
+        if let Some((Action::Cancel, true)) = wrapper.keymap.action_of(event) {
+            return Ok(Some(Transition::Pop));
+        }
+        if let Some(panel) = &mut self.panel {
+            match panel.event(wrapper, event) {
+                Some(PauseOptions::Resume) => return Ok(Some(Transition::Pop)),
+                Some(PauseOptions::Save) => {
+                    self.message = Some(match write_save(MANUAL_SAVE, &self.save) {
+                        Ok(()) => "Game saved".into(),
+                        Err(error) => format!("Could not save: {}", error),
+                    });
+                    self.panel = None;
+                }
+                Some(PauseOptions::Load) => {
+                    let game = read_save(MANUAL_SAVE)
+                        .map_err(|error| error.to_string())
+                        .and_then(|save| {
+                            Game::from_save(save, &wrapper.data).map_err(|error| error.to_string())
+                        });
+                    return Ok(Some(match game {
+                        Ok(game) => Transition::Multiple(vec![
+                            Transition::Pop,
+                            Transition::Swap(Box::new(game)),
+                        ]),
+                        Err(error) => Transition::Push(Box::new(ErrorScreen::new(error))),
+                    }));
+                }
+                Some(PauseOptions::MainMenu) => {
+                    return Ok(Some(Transition::Multiple(vec![
+                        Transition::Pop,
+                        Transition::Pop,
+                    ])))
+                }
+                None => {}
+            }
+        }
+        Ok(None)
+    }
+    fn is_overlay(&self) -> bool {
+        true
+    }
+}
@@ -2,15 +2,40 @@ use crate::Wrapper;
 use async_trait::async_trait;
 use quicksilver::{lifecycle::Event, Result};
 
+pub(crate) enum Transition {
+    /// Replaces the current screen.
+    Swap(Box<dyn Screen>),
+    /// Puts a screen on top of the current one, which comes back once the new screen is popped.
+    Push(Box<dyn Screen>),
+    /// Removes the current screen. Popping the last screen closes the game.
+    Pop,
+    /// Removes every screen, closing the game.
+    Quit,
+    /// Applies several transitions in order. Only the screens on top before and after all of them get told.
+    Multiple(Vec<Transition>),
+}
+
 #[async_trait(?Send)]
 pub(crate) trait Screen {
     async fn draw(&mut self, wrapper: &mut Wrapper<'_>) -> Result<()>;
-    async fn update(&mut self, wrapper: &mut Wrapper<'_>) -> Result<Option<Box<dyn Screen>>>;
+    async fn update(&mut self, wrapper: &mut Wrapper<'_>) -> Result<Option<Transition>>;
     async fn event(
         &mut self,
         _wrapper: &mut Wrapper<'_>,
         _event: &Event,
-    ) -> Result<Option<Box<dyn Screen>>> {
+    ) -> Result<Option<Transition>> {
         Ok(None)
     }
+    /// Called whenever this screen becomes the top of the stack.
+    async fn on_enter(&mut self, _wrapper: &mut Wrapper<'_>) -> Result<()> {
+        Ok(())
+    }
+    /// Called whenever this screen stops being the top of the stack.
+    async fn on_exit(&mut self, _wrapper: &mut Wrapper<'_>) -> Result<()> {
+        Ok(())
+    }
+    /// Overlays get the screen below them drawn first.
+    fn is_overlay(&self) -> bool {
+        false
+    }
 }
@@ -0,0 +1,113 @@
+use super::{
+    controls::ControlsScreen,
+    screen::{Screen, Transition},
+};
+use crate::{
+    panel::{Panel, PanelConfig},
+    Wrapper,
+};
+use async_trait::async_trait;
+use quicksilver::{lifecycle::Event, Result};
+
+pub enum SettingsOptions {
+    ScrollSpeed,
+    ScrollAcceleration,
+    EdgeScrolling,
+    DragPanning,
+    Controls,
+    Back,
+}
+
+fn on_off(value: bool) -> &'static str {
+    if value {
+        "On"
+    } else {
+        "Off"
+    }
+}
+
+pub struct SettingsScreen {
+    panel: Option<Panel<SettingsOptions>>,
+}
+
+impl SettingsScreen {
+    pub fn new() -> Self {
+        Self { panel: None }
+    }
+}
+
+#[async_trait(?Send)]
+impl Screen for SettingsScreen {
+    async fn draw(&mut self, wrapper: &mut Wrapper<'_>) -> Result<()> {
+        if let Some(panel) = &self.panel {
+            panel.draw([0., 0.].into(), wrapper)?;
+        }
+        Ok(())
+    }
+    async fn update(&mut self, wrapper: &mut Wrapper<'_>) -> Result<Option<Transition>> {
+        if let None = self.panel {
+            let settings = &wrapper.settings;
+            self.panel = Some(Panel::new(PanelConfig {
+                options: vec![
+                    (
+                        format!("Scroll speed: {}", settings.scroll_speed.get_name()),
+                        SettingsOptions::ScrollSpeed,
+                    ),
+                    (
+                        format!("Acceleration: {}", settings.scroll_acceleration.get_name()),
+                        SettingsOptions::ScrollAcceleration,
+                    ),
+                    (
+                        format!("Edge scrolling: {}", on_off(settings.edge_scrolling)),
+                        SettingsOptions::EdgeScrolling,
+                    ),
+                    (
+                        format!("Drag with middle mouse: {}", on_off(settings.drag_panning)),
+                        SettingsOptions::DragPanning,
+                    ),
+                    ("Controls".into(), SettingsOptions::Controls),
+                    ("Back".into(), SettingsOptions::Back),
+                ],
+                font: wrapper.get_font(20.).await?,
+                top_left: wrapper.get_pos_vector(0.1, 0.2),
+                width: 350.,
+                background: wrapper.get_image("pixel.png".into()).await?,
+                text_size: 30.,
+            }));
+        }
+        Ok(None)
+    }
+    async fn event(
+        &mut self,
+        wrapper: &mut Wrapper<'_>,
+        event: &Event,
+    ) -> Result<Option<Transition>> {
+        if let Some(panel) = &mut self.panel {
+            match panel.event(wrapper, event) {
+                Some(SettingsOptions::ScrollSpeed) => {
+                    wrapper.settings.scroll_speed = wrapper.settings.scroll_speed.next();
+                    self.panel = None;
+                }
+                Some(SettingsOptions::ScrollAcceleration) => {
+                    wrapper.settings.scroll_acceleration =
+                        wrapper.settings.scroll_acceleration.next();
+                    self.panel = None;
+                }
+                Some(SettingsOptions::EdgeScrolling) => {
+                    wrapper.settings.edge_scrolling = !wrapper.settings.edge_scrolling;
+                    self.panel = None;
+                }
+                Some(SettingsOptions::DragPanning) => {
+                    wrapper.settings.drag_panning = !wrapper.settings.drag_panning;
+                    self.panel = None;
+                }
+                Some(SettingsOptions::Controls) => {
+                    return Ok(Some(Transition::Push(Box::new(ControlsScreen::new()))))
+                }
+                Some(SettingsOptions::Back) => return Ok(Some(Transition::Pop)),
+                None => {}
+            }
+        }
+        Ok(None)
+    }
+}
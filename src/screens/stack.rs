@@ -0,0 +1,93 @@
+use super::screen::{Screen, Transition};
+use crate::Wrapper;
+use quicksilver::{geom::Transform, lifecycle::Event, Result};
+
+fn flatten(transition: Transition, into: &mut Vec<Transition>) {
+    match transition {
+        Transition::Multiple(transitions) => transitions
+            .into_iter()
+            .for_each(|transition| flatten(transition, into)),
+        transition => into.push(transition),
+    }
+}
+
+pub(crate) struct ScreenStack {
+    screens: Vec<Box<dyn Screen>>,
+}
+
+impl ScreenStack {
+    pub async fn new(mut first: Box<dyn Screen>, wrapper: &mut Wrapper<'_>) -> Result<Self> {
+        first.on_enter(wrapper).await?;
+        Ok(Self {
+            screens: vec![first],
+        })
+    }
+    pub fn is_empty(&self) -> bool {
+        self.screens.is_empty()
+    }
+    async fn exit_top(&mut self, wrapper: &mut Wrapper<'_>) -> Result<()> {
+        if let Some(screen) = self.screens.last_mut() {
+            screen.on_exit(wrapper).await?;
+        }
+        Ok(())
+    }
+    async fn enter_top(&mut self, wrapper: &mut Wrapper<'_>) -> Result<()> {
+        if let Some(screen) = self.screens.last_mut() {
+            screen.on_enter(wrapper).await?;
+        }
+        Ok(())
+    }
+    async fn apply(
+        &mut self,
+        transition: Option<Transition>,
+        wrapper: &mut Wrapper<'_>,
+    ) -> Result<()> {
+        let transition = match transition {
+            Some(x) => x,
+            None => return Ok(()),
+        };
+        let mut transitions = Vec::new();
+        flatten(transition, &mut transitions);
+        self.exit_top(wrapper).await?;
+        for transition in transitions {
+            match transition {
+                Transition::Swap(screen) => {
+                    self.screens.pop();
+                    self.screens.push(screen);
+                }
+                Transition::Push(screen) => self.screens.push(screen),
+                Transition::Pop => {
+                    self.screens.pop();
+                }
+                Transition::Quit => self.screens.clear(),
+                Transition::Multiple(_) => unreachable!(),
+            }
+        }
+        self.enter_top(wrapper).await
+    }
+    pub async fn draw(&mut self, wrapper: &mut Wrapper<'_>) -> Result<()> {
+        let mut first_visible = self.screens.len().saturating_sub(1);
+        while first_visible > 0 && self.screens[first_visible].is_overlay() {
+            first_visible -= 1;
+        }
+        for screen in &mut self.screens[first_visible..] {
+            wrapper.gfx.set_transform(Transform::IDENTITY);
+            screen.draw(wrapper).await?;
+        }
+        Ok(())
+    }
+    pub async fn update(&mut self, wrapper: &mut Wrapper<'_>) -> Result<()> {
+        let transition = match self.screens.last_mut() {
+            Some(screen) => screen.update(wrapper).await?,
+            None => None,
+        };
+        self.apply(transition, wrapper).await
+    }
+    pub async fn event(&mut self, wrapper: &mut Wrapper<'_>, event: &Event) -> Result<()> {
+        let transition = match self.screens.last_mut() {
+            Some(screen) => screen.event(wrapper, event).await?,
+            None => None,
+        };
+        self.apply(transition, wrapper).await
+    }
+}
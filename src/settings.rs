@@ -0,0 +1,83 @@
+#[derive(Clone, Copy, Eq, PartialEq)]
+pub enum ScrollSpeed {
+    Slow,
+    Normal,
+    Fast,
+}
+
+impl ScrollSpeed {
+    pub fn get_pixels_per_update(self) -> f32 {
+        match self {
+            ScrollSpeed::Slow => 5.,
+            ScrollSpeed::Normal => 10.,
+            ScrollSpeed::Fast => 20.,
+        }
+    }
+    pub fn next(self) -> Self {
+        match self {
+            ScrollSpeed::Slow => ScrollSpeed::Normal,
+            ScrollSpeed::Normal => ScrollSpeed::Fast,
+            ScrollSpeed::Fast => ScrollSpeed::Slow,
+        }
+    }
+    pub fn get_name(self) -> &'static str {
+        match self {
+            ScrollSpeed::Slow => "Slow",
+            ScrollSpeed::Normal => "Normal",
+            ScrollSpeed::Fast => "Fast",
+        }
+    }
+}
+
+/// How fast the camera gets up to speed and slows down again.
+#[derive(Clone, Copy, Eq, PartialEq)]
+pub enum ScrollAcceleration {
+    Instant,
+    Quick,
+    Smooth,
+}
+
+impl ScrollAcceleration {
+    /// The part of the full scroll speed that gets added or removed each update.
+    pub fn get_step(self) -> f32 {
+        match self {
+            ScrollAcceleration::Instant => 1.,
+            ScrollAcceleration::Quick => 0.2,
+            ScrollAcceleration::Smooth => 0.07,
+        }
+    }
+    pub fn next(self) -> Self {
+        match self {
+            ScrollAcceleration::Instant => ScrollAcceleration::Quick,
+            ScrollAcceleration::Quick => ScrollAcceleration::Smooth,
+            ScrollAcceleration::Smooth => ScrollAcceleration::Instant,
+        }
+    }
+    pub fn get_name(self) -> &'static str {
+        match self {
+            ScrollAcceleration::Instant => "Instant",
+            ScrollAcceleration::Quick => "Quick",
+            ScrollAcceleration::Smooth => "Smooth",
+        }
+    }
+}
+
+pub struct GameSettings {
+    pub scroll_speed: ScrollSpeed,
+    pub scroll_acceleration: ScrollAcceleration,
+    /// Scroll when the cursor is close to the edge of the window.
+    pub edge_scrolling: bool,
+    /// Drag the map around while holding the middle mouse button.
+    pub drag_panning: bool,
+}
+
+impl Default for GameSettings {
+    fn default() -> Self {
+        Self {
+            scroll_speed: ScrollSpeed::Normal,
+            scroll_acceleration: ScrollAcceleration::Quick,
+            edge_scrolling: true,
+            drag_panning: true,
+        }
+    }
+}